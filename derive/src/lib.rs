@@ -0,0 +1,266 @@
+//! `#[derive(SheetRow)]` for `serde-google-sheets`. Use it through the
+//! `derive` feature of that crate rather than depending on this one directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parenthesized, parse_macro_input, token, Data, DeriveInput, Error, Fields, LitStr, Result,
+    Token,
+};
+
+/// Implements `SheetRow` for a struct with named fields. Field names follow
+/// `#[serde(rename = "...")]` on the field and `#[serde(rename_all = "...")]`
+/// on the struct, as serde applies them.
+///
+/// Fields accept `#[sheet(...)]` attributes:
+///
+/// - `column = "C"` reads the field from a column letter instead of a header
+/// - `header = "Unit Price"` reads the field from a differently named header
+/// - `required` fails before any row is read if the column is missing
+/// - `format = "date"` rejects cells with a different number format, one of
+///   `text`, `number`, `percent`, `currency`, `date`, `time`, `date_time` or
+///   `scientific`
+#[proc_macro_derive(SheetRow, attributes(sheet))]
+pub fn derive_sheet_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "SheetRow requires a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "SheetRow can only be derived for structs",
+            ))
+        }
+    };
+
+    let rename_all = match serde_name(&input.attrs, "rename_all") {
+        Some(rule) => Some(RenameRule::parse(&rule)?),
+        None => None,
+    };
+    let columns = fields
+        .iter()
+        .map(|field| column(field, rename_all))
+        .collect::<Result<Vec<_>>>()?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::serde_google_sheets::SheetRow for #name #ty_generics #where_clause {
+            fn columns() -> ::std::vec::Vec<::serde_google_sheets::Column> {
+                ::std::vec![#(#columns),*]
+            }
+        }
+    })
+}
+
+fn column(field: &syn::Field, rename_all: Option<RenameRule>) -> Result<TokenStream2> {
+    let ident = field.ident.as_ref().expect("named field");
+    let name = match serde_name(&field.attrs, "rename") {
+        Some(name) => name.value(),
+        None => {
+            let name = ident.to_string().trim_start_matches("r#").to_owned();
+            match rename_all {
+                Some(rule) => rule.apply(&name),
+                None => name,
+            }
+        }
+    };
+    let mut header = None;
+    let mut letter = None;
+    let mut required = false;
+    let mut format = None;
+
+    for attr in &field.attrs {
+        if !attr.path().is_ident("sheet") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("column") {
+                let value = meta.value()?.parse::<LitStr>()?;
+                let text = value.value();
+                if text.is_empty()
+                    || text.len() > 3
+                    || !text.bytes().all(|b| b.is_ascii_alphabetic())
+                {
+                    return Err(Error::new_spanned(
+                        value,
+                        "expected column letters such as \"C\" or \"AB\"",
+                    ));
+                }
+                letter = Some(text.to_ascii_uppercase());
+            } else if meta.path.is_ident("header") {
+                header = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("required") {
+                required = true;
+            } else if meta.path.is_ident("format") {
+                let value = meta.value()?.parse::<LitStr>()?;
+                format = Some(match value.value().as_str() {
+                    "text" => quote!(Text),
+                    "number" => quote!(Number),
+                    "percent" => quote!(Percent),
+                    "currency" => quote!(Currency),
+                    "date" => quote!(Date),
+                    "time" => quote!(Time),
+                    "date_time" => quote!(DateTime),
+                    "scientific" => quote!(Scientific),
+                    _ => {
+                        return Err(Error::new_spanned(
+                            value,
+                            "expected one of text, number, percent, currency, date, time, \
+                             date_time or scientific",
+                        ))
+                    }
+                });
+            } else {
+                return Err(meta.error("unknown sheet attribute"));
+            }
+            Ok(())
+        })?;
+    }
+
+    if letter.is_some() && header.is_some() {
+        return Err(Error::new_spanned(
+            ident,
+            "a field can't have both a column and a header",
+        ));
+    }
+
+    let header = option(header.map(|h| quote!(#h)));
+    let letter = option(letter.map(|l| quote!(#l)));
+    let format = option(format.map(|f| quote!(::serde_google_sheets::ColumnFormat::#f)));
+
+    Ok(quote! {
+        ::serde_google_sheets::Column {
+            field: #name,
+            header: #header,
+            letter: #letter,
+            required: #required,
+            format: #format,
+        }
+    })
+}
+
+// The name serde deserializes with, from `#[serde(<key> = "...")]` or
+// `#[serde(<key>(deserialize = "..."))]`. Everything else in the attribute is
+// left for serde to parse (and complain about).
+fn serde_name(attrs: &[syn::Attribute], key: &str) -> Option<LitStr> {
+    let mut name = None;
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident(key) && meta.input.peek(Token![=]) {
+                name = Some(meta.value()?.parse::<LitStr>()?);
+            } else if meta.path.is_ident(key) {
+                meta.parse_nested_meta(|nested| {
+                    let value = nested.value()?.parse::<LitStr>()?;
+                    if nested.path.is_ident("deserialize") {
+                        name = Some(value);
+                    }
+                    Ok(())
+                })?;
+            } else if meta.input.peek(Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            } else if meta.input.peek(token::Paren) {
+                let content;
+                parenthesized!(content in meta.input);
+                content.parse::<TokenStream2>()?;
+            }
+            Ok(())
+        });
+    }
+
+    name
+}
+
+// The casing conventions of serde's `rename_all`, applied to snake_case field
+// names the way serde does.
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn parse(rule: &LitStr) -> Result<Self> {
+        Ok(match rule.value().as_str() {
+            "lowercase" => RenameRule::Lower,
+            "UPPERCASE" => RenameRule::Upper,
+            "PascalCase" => RenameRule::Pascal,
+            "camelCase" => RenameRule::Camel,
+            "snake_case" => RenameRule::Snake,
+            "SCREAMING_SNAKE_CASE" => RenameRule::ScreamingSnake,
+            "kebab-case" => RenameRule::Kebab,
+            "SCREAMING-KEBAB-CASE" => RenameRule::ScreamingKebab,
+            _ => {
+                return Err(Error::new_spanned(
+                    rule,
+                    "expected one of lowercase, UPPERCASE, PascalCase, camelCase, snake_case, \
+                     SCREAMING_SNAKE_CASE, kebab-case or SCREAMING-KEBAB-CASE",
+                ))
+            }
+        })
+    }
+
+    fn apply(self, field: &str) -> String {
+        match self {
+            RenameRule::Lower | RenameRule::Snake => field.to_owned(),
+            RenameRule::Upper | RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+            RenameRule::Pascal => {
+                let mut pascal = String::new();
+                let mut capitalize = true;
+                for c in field.chars() {
+                    if c == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(c.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(c);
+                    }
+                }
+                pascal
+            }
+            RenameRule::Camel => {
+                let pascal = RenameRule::Pascal.apply(field);
+                let mut chars = pascal.chars();
+                match chars.next() {
+                    Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                    None => pascal,
+                }
+            }
+            RenameRule::Kebab => field.replace('_', "-"),
+            RenameRule::ScreamingKebab => RenameRule::ScreamingSnake.apply(field).replace('_', "-"),
+        }
+    }
+}
+
+fn option(value: Option<TokenStream2>) -> TokenStream2 {
+    match value {
+        Some(value) => quote!(::std::option::Option::Some(#value)),
+        None => quote!(::std::option::Option::None),
+    }
+}
@@ -0,0 +1,128 @@
+use google_sheets4::api::{CellData, ExtendedValue, GridData, RowData};
+use serde::Deserialize;
+use serde_google_sheets::{
+    from_grid_data_with_options, Column, ColumnFormat, Error, Options, SheetRow,
+};
+
+#[derive(Deserialize, SheetRow, PartialEq, Debug)]
+struct Item {
+    #[sheet(required, format = "text")]
+    name: String,
+    #[sheet(header = "Unit Price")]
+    price: f64,
+    #[serde(rename = "code")]
+    #[sheet(column = "d")]
+    sku: Option<String>,
+}
+
+fn text(s: &str) -> CellData {
+    CellData {
+        formatted_value: Some(s.to_owned()),
+        effective_value: Some(ExtendedValue {
+            string_value: Some(s.to_owned()),
+            ..ExtendedValue::default()
+        }),
+        ..CellData::default()
+    }
+}
+
+fn number(n: f64) -> CellData {
+    CellData {
+        formatted_value: Some(n.to_string()),
+        effective_value: Some(ExtendedValue {
+            number_value: Some(n),
+            ..ExtendedValue::default()
+        }),
+        ..CellData::default()
+    }
+}
+
+fn grid(rows: Vec<Vec<CellData>>) -> GridData {
+    GridData {
+        row_data: Some(
+            rows.into_iter()
+                .map(|values| RowData {
+                    values: Some(values),
+                })
+                .collect(),
+        ),
+        ..GridData::default()
+    }
+}
+
+#[test]
+fn derives_columns() {
+    assert_eq!(
+        Item::columns(),
+        vec![
+            Column {
+                field: "name",
+                header: None,
+                letter: None,
+                required: true,
+                format: Some(ColumnFormat::Text),
+            },
+            Column {
+                field: "price",
+                header: Some("Unit Price"),
+                letter: None,
+                required: false,
+                format: None,
+            },
+            Column {
+                field: "code",
+                header: None,
+                letter: Some("D"),
+                required: false,
+                format: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn reads_mapped_columns() {
+    let data = grid(vec![
+        vec![text("Unit Price"), text("name")],
+        vec![number(2.0), text("Pear")],
+        vec![number(3.5), text("Plum"), text(""), text("P-2")],
+    ]);
+
+    let items: Vec<Item> = from_grid_data_with_options(&data, &Options::for_row::<Item>()).unwrap();
+    assert_eq!(
+        items,
+        vec![
+            Item {
+                name: "Pear".to_owned(),
+                price: 2.0,
+                sku: None,
+            },
+            Item {
+                name: "Plum".to_owned(),
+                price: 3.5,
+                sku: Some("P-2".to_owned()),
+            },
+        ]
+    );
+
+    let data = grid(vec![vec![text("Unit Price")]]);
+    let err = from_grid_data_with_options::<Vec<Item>>(&data, &Options::for_row::<Item>());
+    assert!(matches!(err, Err(Error::MissingColumn(name)) if name == "name"));
+}
+
+#[derive(Deserialize, SheetRow)]
+#[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
+struct Renamed {
+    unit_price: f64,
+    #[serde(rename = "SKU")]
+    sku: String,
+    #[sheet(header = "Notes")]
+    extra_notes: String,
+}
+
+#[test]
+fn follows_rename_all() {
+    let fields: Vec<_> = Renamed::columns().iter().map(|c| c.field).collect();
+    assert_eq!(fields, vec!["unitPrice", "SKU", "extraNotes"]);
+}
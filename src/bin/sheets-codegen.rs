@@ -0,0 +1,80 @@
+//! Generates a `#[derive(Deserialize)]` struct for a sheet.
+//!
+//! Reads either a saved `spreadsheets.get` response (with grid data) or a
+//! single `GridData` object as JSON, and prints a struct whose fields follow
+//! the header row and whose types follow the values below it.
+//!
+//! Usage: `sheets-codegen <file.json> [--sheet <title>] [--name <StructName>]`
+
+use std::error::Error;
+use std::process::ExitCode;
+
+use google_sheets4::api::GridData;
+use serde_google_sheets::schema::{generate_struct, infer_schema};
+use serde_google_sheets::sheet_grid_data;
+
+const USAGE: &str = "usage: sheets-codegen <file.json> [--sheet <title>] [--name <StructName>]";
+
+struct Args {
+    path: String,
+    sheet: Option<String>,
+    name: String,
+}
+
+fn parse_args() -> Option<Args> {
+    let mut args = std::env::args().skip(1);
+    let mut path = None;
+    let mut sheet = None;
+    let mut name = "Row".to_owned();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--sheet" => sheet = Some(args.next()?),
+            "--name" => name = args.next()?,
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+            _ => return None,
+        }
+    }
+
+    Some(Args {
+        path: path?,
+        sheet,
+        name,
+    })
+}
+
+fn load_grid_data(json: &str, sheet: Option<&str>) -> Result<GridData, Box<dyn Error>> {
+    let value: serde_json::Value = serde_json::from_str(json)?;
+
+    if value.get("rowData").is_some() {
+        return Ok(serde_json::from_value(value)?);
+    }
+
+    Ok(sheet_grid_data(serde_json::from_value(value)?, sheet)?)
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    let json = std::fs::read_to_string(&args.path)?;
+    let grid_data = load_grid_data(&json, args.sheet.as_deref())?;
+    let columns = infer_schema(&grid_data)?;
+
+    println!("use serde::Deserialize;\n");
+    print!("{}", generate_struct(&args.name, &columns));
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let Some(args) = parse_args() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("sheets-codegen: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,309 @@
+//! Converts a saved `spreadsheets.get` response into JSON, NDJSON or CSV.
+//!
+//! Rows are read with the same typing rules as the deserializer: bools and
+//! numbers keep their type, dates and times use their formatted text and
+//! blank rows are skipped.
+//!
+//! Usage: `sheets-convert <file.json> [--sheet <title>] [--format json|ndjson|csv]`
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+use google_sheets4::api::GridData;
+use serde::de::{MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_google_sheets::{sheet_grid_data, MAX_SAFE_INTEGER};
+use serde_json::Value;
+
+const USAGE: &str =
+    "usage: sheets-convert <file.json> [--sheet <title>] [--format json|ndjson|csv]";
+
+#[derive(Clone, Copy)]
+enum Format {
+    Json,
+    Ndjson,
+    Csv,
+}
+
+struct Args {
+    path: String,
+    sheet: Option<String>,
+    format: Format,
+}
+
+fn parse_args() -> Option<Args> {
+    let mut args = std::env::args().skip(1);
+    let mut path = None;
+    let mut sheet = None;
+    let mut format = Format::Json;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--sheet" => sheet = Some(args.next()?),
+            "--format" => {
+                format = match args.next()?.as_str() {
+                    "json" => Format::Json,
+                    "ndjson" => Format::Ndjson,
+                    "csv" => Format::Csv,
+                    _ => return None,
+                }
+            }
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+            _ => return None,
+        }
+    }
+
+    Some(Args {
+        path: path?,
+        sheet,
+        format,
+    })
+}
+
+// A row as (header, value) pairs in column order.
+struct Row(Vec<(String, Value)>);
+
+impl Row {
+    fn get(&self, header: &str) -> Option<&Value> {
+        self.0.iter().find(|(h, _)| h == header).map(|(_, v)| v)
+    }
+}
+
+impl<'de> Deserialize<'de> for Row {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct RowVisitor;
+
+        impl<'de> Visitor<'de> for RowVisitor {
+            type Value = Row;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a sheet row")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Row, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut cells = Vec::new();
+
+                while let Some((header, value)) = map.next_entry::<String, Value>()? {
+                    cells.push((header, integral(value)));
+                }
+
+                Ok(Row(cells))
+            }
+        }
+
+        deserializer.deserialize_map(RowVisitor)
+    }
+}
+
+impl Serialize for Row {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+
+        for (header, value) in &self.0 {
+            map.serialize_entry(header, value)?;
+        }
+
+        map.end()
+    }
+}
+
+// Sheets numbers are all f64; whole numbers are written without a fraction.
+fn integral(value: Value) -> Value {
+    match value.as_f64() {
+        Some(n) if n.fract() == 0.0 && n.abs() <= MAX_SAFE_INTEGER => Value::from(n as i64),
+        _ => value,
+    }
+}
+
+fn headers(grid_data: &GridData) -> Vec<&str> {
+    grid_data
+        .row_data
+        .as_deref()
+        .and_then(|rows| rows.first())
+        .and_then(|row| row.values.as_deref())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|cell| cell.formatted_value.as_deref())
+        .collect()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+fn csv_value(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => csv_field(s),
+        Some(other) => csv_field(&other.to_string()),
+    }
+}
+
+fn write_rows(
+    out: &mut impl Write,
+    format: Format,
+    headers: &[&str],
+    rows: &[Row],
+) -> Result<(), Box<dyn Error>> {
+    match format {
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut *out, rows)?;
+            writeln!(out)?;
+        }
+        Format::Ndjson => {
+            for row in rows {
+                serde_json::to_writer(&mut *out, row)?;
+                writeln!(out)?;
+            }
+        }
+        Format::Csv => {
+            let line: Vec<_> = headers.iter().map(|h| csv_field(h)).collect();
+            writeln!(out, "{}", line.join(","))?;
+
+            for row in rows {
+                let line: Vec<_> = headers.iter().map(|h| csv_value(row.get(h))).collect();
+                writeln!(out, "{}", line.join(","))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// The rows below the header, leaving out blank rows.
+fn read_rows(grid_data: &GridData) -> Result<Vec<Row>, Box<dyn Error>> {
+    let rows: Vec<Option<Row>> = serde_google_sheets::from_grid_data(grid_data)?;
+    Ok(rows.into_iter().flatten().collect())
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    let json = std::fs::read_to_string(&args.path)?;
+    let grid_data = sheet_grid_data(serde_json::from_str(&json)?, args.sheet.as_deref())?;
+    let rows = read_rows(&grid_data)?;
+
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    write_rows(&mut out, args.format, &headers(&grid_data), &rows)?;
+    out.flush()?;
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let Some(args) = parse_args() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("sheets-convert: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+const FIXTURE: &str = r#"{
+    "sheets": [{
+        "properties": {"title": "Fruit"},
+        "data": [{
+            "rowData": [
+                {"values": [
+                    {"formattedValue": "name", "effectiveValue": {"stringValue": "name"}},
+                    {"formattedValue": "price", "effectiveValue": {"stringValue": "price"}},
+                    {"formattedValue": "qty", "effectiveValue": {"stringValue": "qty"}},
+                    {"formattedValue": "in stock", "effectiveValue": {"stringValue": "in stock"}}
+                ]},
+                {"values": [
+                    {"formattedValue": "Apple, red", "effectiveValue": {"stringValue": "Apple, red"}},
+                    {"formattedValue": "0.5", "effectiveValue": {"numberValue": 0.5}},
+                    {"formattedValue": "3", "effectiveValue": {"numberValue": 3}},
+                    {"formattedValue": "TRUE", "effectiveValue": {"boolValue": true}}
+                ]},
+                {"values": []},
+                {"values": [
+                    {"formattedValue": "Pear \"Conference\"", "effectiveValue": {"stringValue": "Pear \"Conference\""}},
+                    {"formattedValue": "1", "effectiveValue": {"numberValue": 1}},
+                    {},
+                    {"formattedValue": "FALSE", "effectiveValue": {"boolValue": false}}
+                ]}
+            ]
+        }]
+    }]
+}"#;
+
+#[cfg(test)]
+fn convert(format: Format) -> String {
+    let grid_data = sheet_grid_data(serde_json::from_str(FIXTURE).unwrap(), None).unwrap();
+    let rows = read_rows(&grid_data).unwrap();
+
+    let mut out = Vec::new();
+    write_rows(&mut out, format, &headers(&grid_data), &rows).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn test_integral() {
+    assert_eq!(integral(Value::from(3.0)), Value::from(3));
+    assert_eq!(integral(Value::from(-3.0)), Value::from(-3));
+    assert_eq!(integral(Value::from(0.5)), Value::from(0.5));
+    assert_eq!(integral(Value::from(1e300)), Value::from(1e300));
+    assert_eq!(integral(Value::from("3")), Value::from("3"));
+}
+
+#[test]
+fn test_csv_quoting() {
+    assert_eq!(csv_field("plain"), "plain");
+    assert_eq!(csv_field("a,b"), "\"a,b\"");
+    assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+
+    assert_eq!(csv_value(None), "");
+    assert_eq!(csv_value(Some(&Value::Null)), "");
+    assert_eq!(csv_value(Some(&Value::from(3))), "3");
+    assert_eq!(csv_value(Some(&Value::from(true))), "true");
+    assert_eq!(csv_value(Some(&Value::from("a,b"))), "\"a,b\"");
+}
+
+#[test]
+fn test_write_rows() {
+    assert_eq!(
+        convert(Format::Csv),
+        "name,price,qty,in stock\n\
+         \"Apple, red\",0.5,3,true\n\
+         \"Pear \"\"Conference\"\"\",1,,false\n"
+    );
+
+    assert_eq!(
+        convert(Format::Ndjson),
+        r#"{"name":"Apple, red","price":0.5,"qty":3,"in stock":true}
+{"name":"Pear \"Conference\"","price":1,"qty":null,"in stock":false}
+"#
+    );
+
+    let json: Value = serde_json::from_str(&convert(Format::Json)).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([
+            {"name": "Apple, red", "price": 0.5, "qty": 3, "in stock": true},
+            {"name": "Pear \"Conference\"", "price": 1, "qty": null, "in stock": false},
+        ])
+    );
+}
@@ -0,0 +1,382 @@
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::error::Result;
+use crate::source::SheetSource;
+use google_sheets4::api::{DeveloperMetadata, Spreadsheet};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+/// A [`SheetSource`] that keeps fetched spreadsheets on disk.
+///
+/// Entries younger than `ttl` are returned as they are. Older entries are
+/// revalidated against the inner source's [`SheetSource::revision`] and
+/// reused when it hasn't changed; otherwise the spreadsheet is fetched again.
+///
+/// For the Sheets API, the revision is the Drive version of the file, read
+/// with the `drive.readonly` scope through the Drive API. Credentials without
+/// that scope, or a project without the Drive API enabled, still work, but
+/// expired entries are then always fetched again (with a warning logged).
+/// Hits and misses are logged at debug level with a `cache` field.
+#[derive(Debug, Clone)]
+pub struct CachedSource<S> {
+    inner: S,
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    // Seconds since the Unix epoch.
+    fetched_at: u64,
+    revision: Option<String>,
+    spreadsheet: Spreadsheet,
+}
+
+impl<S> CachedSource<S>
+where
+    S: SheetSource + Sync,
+{
+    pub fn new(inner: S, dir: impl Into<PathBuf>, ttl: Duration) -> Self {
+        CachedSource {
+            inner,
+            dir: dir.into(),
+            ttl,
+        }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    fn path(&self, spreadsheet_id: &str, request: &[&str]) -> PathBuf {
+        self.dir
+            .join(format!("{}-{:016x}.json", spreadsheet_id, fnv1a(request)))
+    }
+
+    async fn read(&self, path: &PathBuf) -> Option<Entry> {
+        let json = tokio::fs::read(path).await.ok()?;
+
+        serde_json::from_slice(&json).ok()
+    }
+
+    async fn write(&self, path: &PathBuf, entry: &Entry) {
+        let result = async {
+            tokio::fs::create_dir_all(&self.dir).await?;
+            tokio::fs::write(path, serde_json::to_vec(entry)?).await?;
+
+            Ok::<_, crate::error::Error>(())
+        }
+        .await;
+
+        if let Err(err) = result {
+            warn!(path = %path.display(), %err, "failed to write spreadsheet cache");
+        }
+    }
+
+    async fn cached<F>(
+        &self,
+        spreadsheet_id: &str,
+        request: &[&str],
+        fetch: impl FnOnce() -> F,
+    ) -> Result<Spreadsheet>
+    where
+        F: Future<Output = Result<Spreadsheet>>,
+    {
+        let path = self.path(spreadsheet_id, request);
+        let now = now();
+
+        let cached = match self.read(&path).await {
+            Some(entry) if now.saturating_sub(entry.fetched_at) < self.ttl.as_secs() => {
+                debug!(spreadsheet_id, ?request, cache = "hit", "fresh cache entry");
+                return Ok(entry.spreadsheet);
+            }
+            cached => cached,
+        };
+
+        // Without a revision the entry is simply fetched again, which beats
+        // failing a read the inner source could serve.
+        let revision = match self.inner.revision(spreadsheet_id).await {
+            Ok(revision) => revision,
+            Err(err) => {
+                warn!(spreadsheet_id, %err, "failed to look up spreadsheet revision");
+                None
+            }
+        };
+
+        if let Some(mut entry) =
+            cached.filter(|entry| revision.is_some() && entry.revision == revision)
+        {
+            debug!(
+                spreadsheet_id,
+                ?request,
+                cache = "hit",
+                "revision unchanged"
+            );
+
+            entry.fetched_at = now;
+            self.write(&path, &entry).await;
+
+            return Ok(entry.spreadsheet);
+        }
+
+        debug!(
+            spreadsheet_id,
+            ?request,
+            cache = "miss",
+            "fetching spreadsheet"
+        );
+
+        let entry = Entry {
+            fetched_at: now,
+            revision,
+            spreadsheet: fetch().await?,
+        };
+        self.write(&path, &entry).await;
+
+        Ok(entry.spreadsheet)
+    }
+}
+
+impl<S> SheetSource for CachedSource<S>
+where
+    S: SheetSource + Sync,
+{
+    async fn spreadsheet(&self, spreadsheet_id: &str) -> Result<Spreadsheet> {
+        self.cached(spreadsheet_id, &["metadata"], || {
+            self.inner.spreadsheet(spreadsheet_id)
+        })
+        .await
+    }
+
+    async fn grid_data(
+        &self,
+        spreadsheet_id: &str,
+        range: &str,
+        fields: &str,
+    ) -> Result<Spreadsheet> {
+        self.cached(spreadsheet_id, &["grid_data", range, fields], || {
+            self.inner.grid_data(spreadsheet_id, range, fields)
+        })
+        .await
+    }
+
+    async fn revision(&self, spreadsheet_id: &str) -> Result<Option<String>> {
+        self.inner.revision(spreadsheet_id).await
+    }
+
+    async fn search_developer_metadata(
+        &self,
+        spreadsheet_id: &str,
+        key: &str,
+    ) -> Result<Option<Vec<DeveloperMetadata>>> {
+        self.inner
+            .search_developer_metadata(spreadsheet_id, key)
+            .await
+    }
+}
+
+// 64-bit FNV-1a of the request parts, each followed by a NUL byte so that
+// ["ab", "c"] and ["a", "bc"] differ. Unlike `DefaultHasher`, the result is
+// the same across Rust versions and runs, so cache entries stay valid.
+fn fnv1a(request: &[&str]) -> u64 {
+    request
+        .iter()
+        .flat_map(|part| part.bytes().chain([0]))
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|v| v.as_secs())
+        .unwrap_or(0)
+}
+
+#[test]
+fn test_fnv1a() {
+    assert_eq!(0xaf63_bd4c_8601_b7df, fnv1a(&[""]));
+    assert_ne!(fnv1a(&["ab", "c"]), fnv1a(&["a", "bc"]));
+}
+
+#[tokio::test]
+async fn test_cached_source() {
+    use crate::options::Options;
+    use crate::source::{fixture_spreadsheet, from_source, Fixture};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    struct Counting {
+        fixture: Fixture,
+        fetches: AtomicUsize,
+        revision: Mutex<Option<String>>,
+    }
+
+    impl SheetSource for Counting {
+        async fn spreadsheet(&self, spreadsheet_id: &str) -> Result<Spreadsheet> {
+            self.fixture.spreadsheet(spreadsheet_id).await
+        }
+
+        async fn grid_data(
+            &self,
+            spreadsheet_id: &str,
+            range: &str,
+            fields: &str,
+        ) -> Result<Spreadsheet> {
+            self.fetches.fetch_add(1, Ordering::SeqCst);
+            self.fixture.grid_data(spreadsheet_id, range, fields).await
+        }
+
+        async fn revision(&self, _spreadsheet_id: &str) -> Result<Option<String>> {
+            Ok(self.revision.lock().unwrap().clone())
+        }
+    }
+
+    let mut fixture = Fixture::new();
+    fixture.insert(
+        "abc",
+        fixture_spreadsheet(vec![("Sheet1", vec![vec!["name"], vec!["Apple"]])]),
+    );
+
+    let dir =
+        std::env::temp_dir().join(format!("serde-google-sheets-cache-{}", std::process::id()));
+    let counting = Counting {
+        fixture,
+        fetches: AtomicUsize::new(0),
+        revision: Mutex::new(Some("1".to_owned())),
+    };
+
+    // Every entry is expired straight away, so only the revision decides.
+    let source = CachedSource::new(counting, &dir, Duration::ZERO);
+
+    let fetch = || async {
+        let rows: Vec<HashMap<String, String>> = from_source(&source, "abc", &Options::default())
+            .await
+            .unwrap();
+        assert_eq!("Apple", rows[0]["name"]);
+
+        source.inner().fetches.load(Ordering::SeqCst)
+    };
+
+    assert_eq!(1, fetch().await);
+    assert_eq!(1, fetch().await);
+
+    *source.inner().revision.lock().unwrap() = Some("2".to_owned());
+    assert_eq!(2, fetch().await);
+
+    let fresh = CachedSource::new(
+        Counting {
+            fixture: source.inner().fixture.clone(),
+            fetches: AtomicUsize::new(0),
+            revision: Mutex::new(None),
+        },
+        &dir,
+        Duration::from_secs(3600),
+    );
+    let rows: Vec<HashMap<String, String>> = from_source(&fresh, "abc", &Options::default())
+        .await
+        .unwrap();
+    assert_eq!("Apple", rows[0]["name"]);
+    assert_eq!(0, fresh.inner().fetches.load(Ordering::SeqCst));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_cached_source_content_change() {
+    use crate::options::Options;
+    use crate::source::{fixture_spreadsheet, from_source, JsonDir};
+    use std::collections::HashMap;
+
+    let dir = std::env::temp_dir().join(format!(
+        "serde-google-sheets-cache-change-{}",
+        std::process::id()
+    ));
+    let data = dir.join("data");
+    std::fs::create_dir_all(&data).unwrap();
+
+    // Rewrites the spreadsheet file, moving its modification time along so
+    // that the change shows on filesystems with coarse timestamps too.
+    let write = |name: &str, modified: u64| {
+        let spreadsheet = fixture_spreadsheet(vec![("Sheet1", vec![vec!["name"], vec![name]])]);
+        let path = data.join("abc.json");
+        std::fs::write(&path, serde_json::to_vec(&spreadsheet).unwrap()).unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(UNIX_EPOCH + Duration::from_secs(modified))
+            .unwrap();
+    };
+
+    let source = CachedSource::new(JsonDir::new(&data), dir.join("cache"), Duration::ZERO);
+    let name = || async {
+        let rows: Vec<HashMap<String, String>> = from_source(&source, "abc", &Options::default())
+            .await
+            .unwrap();
+        rows[0]["name"].clone()
+    };
+
+    write("Apple", 1_000);
+    assert_eq!("Apple", name().await);
+
+    write("Pear", 2_000);
+    assert_eq!("Pear", name().await);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_cached_source_revision_error() {
+    use crate::error::Error;
+    use crate::options::Options;
+    use crate::source::{fixture_spreadsheet, from_source, Fixture};
+    use std::collections::HashMap;
+
+    struct Unversioned(Fixture);
+
+    impl SheetSource for Unversioned {
+        async fn spreadsheet(&self, spreadsheet_id: &str) -> Result<Spreadsheet> {
+            self.0.spreadsheet(spreadsheet_id).await
+        }
+
+        async fn grid_data(
+            &self,
+            spreadsheet_id: &str,
+            range: &str,
+            fields: &str,
+        ) -> Result<Spreadsheet> {
+            self.0.grid_data(spreadsheet_id, range, fields).await
+        }
+
+        async fn revision(&self, _spreadsheet_id: &str) -> Result<Option<String>> {
+            Err(Error::Message("insufficient scopes".to_owned()))
+        }
+    }
+
+    let mut fixture = Fixture::new();
+    fixture.insert(
+        "abc",
+        fixture_spreadsheet(vec![("Sheet1", vec![vec!["name"], vec!["Apple"]])]),
+    );
+
+    let dir = std::env::temp_dir().join(format!(
+        "serde-google-sheets-cache-unversioned-{}",
+        std::process::id()
+    ));
+    let source = CachedSource::new(Unversioned(fixture), &dir, Duration::ZERO);
+
+    // Expired entries are fetched again rather than failing the read.
+    for _ in 0..2 {
+        let rows: Vec<HashMap<String, String>> = from_source(&source, "abc", &Options::default())
+            .await
+            .unwrap();
+        assert_eq!("Apple", rows[0]["name"]);
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,281 @@
+use futures::stream::{self, StreamExt, TryStreamExt};
+
+use crate::de::from_grid_data_with_options;
+use crate::error::{Error, Result};
+use crate::fields::field_mask;
+use crate::merge::fill_merged_cells;
+use crate::options::Options;
+use crate::range::A1Range;
+use crate::source::{sheet_title, SheetSource};
+use google_sheets4::api::{DimensionProperties, GridData, GridRange, RowData};
+use serde::de::DeserializeOwned;
+use tracing::{debug, instrument};
+
+/// How a large sheet is split into row windows when fetching it.
+#[derive(Debug, Clone)]
+pub struct Chunking {
+    /// Number of rows requested per window.
+    pub rows_per_chunk: u32,
+
+    /// Maximum number of windows fetched at the same time.
+    pub concurrency: usize,
+}
+
+impl Default for Chunking {
+    fn default() -> Self {
+        Chunking {
+            rows_per_chunk: 5000,
+            concurrency: 4,
+        }
+    }
+}
+
+/// Deserializes the rows of the first sheet of a spreadsheet by fetching it
+/// in row windows, for sheets too large for a single `includeGridData`
+/// request.
+///
+/// The sheet's row count is read from its grid properties first. Windows are
+/// fetched concurrently and deserialized in order as they arrive, each behind
+/// the header row of the first window, so only the deserialized rows and at
+/// most `concurrency` windows are held at once. `T` is the type of a single
+/// row; since windows are read one at a time, groups of rows (see
+/// [`Grouping`](crate::Grouping)) don't carry over from one window to the
+/// next.
+#[instrument(skip(source, chunking, options))]
+pub async fn from_source_chunked<T, S>(
+    source: &S,
+    spreadsheet_id: &str,
+    chunking: &Chunking,
+    options: &Options,
+) -> Result<Vec<T>>
+where
+    T: DeserializeOwned,
+    S: SheetSource,
+{
+    let metadata = source.spreadsheet(spreadsheet_id).await?;
+
+    let sheet = metadata
+        .sheets
+        .as_deref()
+        .and_then(|sheets| sheets.first())
+        .ok_or(Error::MissingSheet)?;
+    let title = sheet_title(sheet).ok_or(Error::MissingSheet)?;
+    let merges = sheet.merges.as_deref().unwrap_or_default();
+    let row_count = sheet
+        .properties
+        .as_ref()
+        .and_then(|p| p.grid_properties.as_ref())
+        .and_then(|p| p.row_count)
+        .ok_or(Error::NotGridSheet)?
+        .max(0) as u32;
+
+    let rows_per_chunk = chunking.rows_per_chunk.max(1);
+    let ranges: Vec<String> = (0..row_count)
+        .step_by(rows_per_chunk as usize)
+        .map(|start| {
+            A1Range {
+                start_row: Some(start),
+                end_row: Some(start.saturating_add(rows_per_chunk).min(row_count)),
+                ..A1Range::sheet(title)
+            }
+            .to_string()
+        })
+        .collect();
+
+    debug!(row_count, chunks = ranges.len(), "fetching sheet in chunks");
+
+    let fields = field_mask(options);
+    let mut windows = stream::iter(&ranges)
+        .map(|range| source.grid_data(spreadsheet_id, range, &fields))
+        .buffered(chunking.concurrency.max(1));
+
+    let mut reader = WindowReader::default();
+    let mut rows = Vec::new();
+
+    while let Some(window) = windows.try_next().await? {
+        let window = window
+            .sheets
+            .into_iter()
+            .flatten()
+            .flat_map(|sheet| sheet.data.into_iter().flatten())
+            .next();
+
+        if let Some(grid_data) = window.and_then(|window| reader.next(window, merges, options)) {
+            rows.extend(from_grid_data_with_options::<Vec<T>>(&grid_data, options)?);
+        }
+    }
+
+    if reader.header.is_none() {
+        return Err(Error::ZeroRows);
+    }
+
+    Ok(rows)
+}
+
+// Turns the windows of a sheet, in order, into grids that can each be
+// deserialized on their own: the header row followed by the window's rows.
+#[derive(Default)]
+struct WindowReader {
+    header: Option<RowData>,
+    column_metadata: Option<Vec<DimensionProperties>>,
+    // Sheet index of the row after the last one read so far.
+    next_row: usize,
+    // The last row of the previous window, with merged cells filled in.
+    carry: Option<RowData>,
+}
+
+impl WindowReader {
+    fn next(
+        &mut self,
+        window: GridData,
+        merges: &[GridRange],
+        options: &Options,
+    ) -> Option<GridData> {
+        let mut rows = window.row_data.filter(|rows| !rows.is_empty())?;
+        let mut row_metadata = window.row_metadata.unwrap_or_default();
+
+        // The API leaves out trailing blank rows, so the rows between the
+        // previous window's last row and this one's start are blank.
+        let start = window.start_row.unwrap_or(0).max(0) as usize;
+        let blank = start.saturating_sub(self.next_row);
+        let blank_row = RowData {
+            values: Some(Vec::new()),
+        };
+        rows.splice(0..0, std::iter::repeat_n(blank_row, blank));
+        if !row_metadata.is_empty() {
+            row_metadata.splice(
+                0..0,
+                std::iter::repeat_n(DimensionProperties::default(), blank),
+            );
+        }
+
+        let mut first = start - blank;
+        self.next_row = first + rows.len();
+
+        if self.header.is_none() {
+            self.header = Some(rows.remove(0));
+            if !row_metadata.is_empty() {
+                row_metadata.remove(0);
+            }
+            self.column_metadata = window.column_metadata;
+            first += 1;
+        }
+
+        let header = self.header.clone()?;
+
+        // The row above the window goes first, so that merges continuing
+        // into the window are filled from it, and is then replaced by the
+        // header.
+        let above = self.carry.take().unwrap_or_else(|| header.clone());
+        rows.insert(0, above);
+        if !row_metadata.is_empty() {
+            row_metadata.insert(0, DimensionProperties::default());
+        }
+
+        let mut grid_data = GridData {
+            start_row: Some(first as i32 - 1),
+            start_column: window.start_column,
+            row_data: Some(rows),
+            row_metadata: Some(row_metadata),
+            column_metadata: self.column_metadata.clone(),
+        };
+
+        if options.fill_merged_cells {
+            let merges: Vec<_> = merges
+                .iter()
+                .map(|merge| GridRange {
+                    start_row_index: merge.start_row_index.map(|row| row.max(first as i32 - 1)),
+                    ..merge.clone()
+                })
+                .collect();
+            fill_merged_cells(&mut grid_data, &merges);
+        }
+
+        let rows = grid_data.row_data.as_mut()?;
+        self.carry = rows.last().cloned();
+        rows[0] = header;
+
+        Some(grid_data)
+    }
+}
+
+#[tokio::test]
+async fn test_chunked() {
+    use crate::source::{fixture_spreadsheet, Fixture};
+    use google_sheets4::api::GridProperties;
+    use std::collections::HashMap;
+
+    let mut spreadsheet = fixture_spreadsheet(vec![(
+        "Sheet1",
+        vec![
+            vec!["n"],
+            vec!["1"],
+            vec!["2"],
+            vec![],
+            vec!["4"],
+            vec!["5"],
+            vec!["6"],
+        ],
+    )]);
+
+    let properties = spreadsheet.sheets.as_mut().unwrap()[0]
+        .properties
+        .as_mut()
+        .unwrap();
+    properties.grid_properties = Some(GridProperties {
+        row_count: Some(9),
+        ..GridProperties::default()
+    });
+
+    let mut fixture = Fixture::new();
+    fixture.insert("abc", spreadsheet.clone());
+
+    let chunking = Chunking {
+        rows_per_chunk: 2,
+        concurrency: 2,
+    };
+
+    let result: Vec<Option<HashMap<String, String>>> =
+        from_source_chunked(&fixture, "abc", &chunking, &Options::default())
+            .await
+            .unwrap();
+
+    let values: Vec<_> = result
+        .iter()
+        .map(|row| row.as_ref().map(|row| row["n"].as_str()))
+        .collect();
+
+    assert_eq!(
+        vec![Some("1"), Some("2"), None, Some("4"), Some("5"), Some("6")],
+        values
+    );
+
+    // A merge that starts in one window and ends two windows later.
+    spreadsheet.sheets.as_mut().unwrap()[0].merges = Some(vec![GridRange {
+        start_row_index: Some(4),
+        end_row_index: Some(7),
+        start_column_index: Some(0),
+        end_column_index: Some(1),
+        ..GridRange::default()
+    }]);
+    fixture.insert("merged", spreadsheet);
+
+    let options = Options {
+        fill_merged_cells: true,
+        ..Options::default()
+    };
+    let result: Vec<Option<HashMap<String, String>>> =
+        from_source_chunked(&fixture, "merged", &chunking, &options)
+            .await
+            .unwrap();
+
+    let values: Vec<_> = result
+        .iter()
+        .map(|row| row.as_ref().map(|row| row["n"].as_str()))
+        .collect();
+
+    assert_eq!(
+        vec![Some("1"), Some("2"), None, Some("4"), Some("4"), Some("4")],
+        values
+    );
+}
@@ -0,0 +1,35 @@
+use std::error::Error as StdError;
+
+use google_sheets4::hyper::client::connect::Connection;
+use google_sheets4::hyper::service::Service;
+use google_sheets4::hyper::Uri;
+use tokio::io::{AsyncRead, AsyncWrite};
+
+/// Any hyper connector `google_sheets4::Sheets` can issue requests with, such
+/// as the default `HttpsConnector<HttpConnector>`, a proxy connector or a
+/// plain `HttpConnector` talking to a test server.
+pub trait Connector:
+    Service<
+        Uri,
+        Response: Connection + AsyncRead + AsyncWrite + Send + Unpin + 'static,
+        Future: Send + Unpin + 'static,
+        Error: Into<Box<dyn StdError + Send + Sync>>,
+    > + Clone
+    + Send
+    + Sync
+    + 'static
+{
+}
+
+impl<C> Connector for C where
+    C: Service<
+            Uri,
+            Response: Connection + AsyncRead + AsyncWrite + Send + Unpin + 'static,
+            Future: Send + Unpin + 'static,
+            Error: Into<Box<dyn StdError + Send + Sync>>,
+        > + Clone
+        + Send
+        + Sync
+        + 'static
+{
+}
@@ -1,13 +1,26 @@
+use std::collections::HashMap;
+use std::io::Read;
 use std::iter::Peekable;
+use std::num::{IntErrorKind, ParseIntError};
+use std::str::FromStr;
 
+use crate::connector::Connector;
 use crate::error::{Error, Result};
-use google_sheets4::api::{CellData, ExtendedValue, GridData};
-use google_sheets4::hyper::client::HttpConnector;
-use google_sheets4::hyper_rustls::HttpsConnector;
+use crate::fields::field_mask;
+use crate::header;
+use crate::merge::fill_merged_cells;
+use crate::options::{EmptyItems, Options};
+use crate::range;
+use crate::row::{Column, ColumnFormat};
+use crate::source::sheet_title;
+use crate::text::TextDeserializer;
+use google_sheets4::api::{CellData, ExtendedValue, GridData, Spreadsheet};
+use serde::de::value::{BorrowedStrDeserializer, SeqDeserializer};
 use serde::de::{
-    self, DeserializeOwned, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess,
-    Visitor,
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
 };
+use serde::forward_to_deserialize_any;
 use serde::Deserialize;
 use tracing::instrument;
 
@@ -18,44 +31,270 @@ where
     rows: Peekable<I>,
     types: smallmap::Map<usize, Option<&'de str>>,
     key_idx: Option<usize>,
+    start_row: u32,
+    start_column: u32,
     row_idx: u32,
+    in_row: bool,
     cur_type: Option<&'de str>,
     parsing_enum: bool,
+    options: Options,
+    // Grouping state: whether the children key was handed out for the
+    // current parent, whether its value is still to be deserialized, whether
+    // a child row is being deserialized, and whether the child rows already
+    // advanced past the current parent.
+    group_emitted: bool,
+    group_pending: bool,
+    in_group: bool,
+    row_consumed: bool,
+    // Expected number formats by column index, from `Options::columns`.
+    formats: Vec<(usize, ColumnFormat)>,
+    headers_checked: bool,
+    // Rows hidden by the user or a filter that are to be skipped, by index
+    // into the grid.
+    hidden_rows: Vec<bool>,
+    // Dropdown values by column index, for columns not yet checked against
+    // the variants of the enum they're read into.
+    dropdowns: Vec<(usize, Vec<&'de str>)>,
 }
 
 #[instrument(skip(sheets))]
-pub async fn from_spreadsheet<T>(
-    sheets: &google_sheets4::Sheets<HttpsConnector<HttpConnector>>,
+pub async fn from_spreadsheet<T, C>(
+    sheets: &google_sheets4::Sheets<C>,
     spreadsheet_id: &str,
 ) -> Result<T>
 where
     T: DeserializeOwned,
+    C: Connector,
 {
-    let spreadsheet = sheets
+    from_spreadsheet_with_options(sheets, spreadsheet_id, &Options::default()).await
+}
+
+#[instrument(skip(sheets, options))]
+pub async fn from_spreadsheet_with_options<T, C>(
+    sheets: &google_sheets4::Sheets<C>,
+    spreadsheet_id: &str,
+    options: &Options,
+) -> Result<T>
+where
+    T: DeserializeOwned,
+    C: Connector,
+{
+    let (_, spreadsheet) = sheets
         .spreadsheets()
         .get(spreadsheet_id)
         .include_grid_data(true)
+        .param("fields", &field_mask(options))
         .doit()
         .await?;
 
-    let grid_data = spreadsheet
-        .1
+    from_fetched_spreadsheet(spreadsheet, options)
+}
+
+/// Deserializes the first sheet of a `spreadsheets.get` response saved as
+/// JSON, e.g. a captured test fixture. The response must include grid data.
+#[instrument(skip(json))]
+pub fn from_spreadsheet_json<T>(json: &str) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    from_fetched_spreadsheet(serde_json::from_str(json)?, &Options::default())
+}
+
+/// Like [`from_spreadsheet_json`], reading the JSON from `reader`.
+#[instrument(skip(reader))]
+pub fn from_spreadsheet_reader<T, R>(reader: R) -> Result<T>
+where
+    T: DeserializeOwned,
+    R: Read,
+{
+    from_fetched_spreadsheet(serde_json::from_reader(reader)?, &Options::default())
+}
+
+/// Deserializes the first sheet of an already fetched or parsed spreadsheet.
+/// This is what [`from_spreadsheet_with_options`] does once the response has
+/// arrived.
+#[instrument(skip(spreadsheet, options))]
+pub fn from_fetched_spreadsheet<T>(mut spreadsheet: Spreadsheet, options: &Options) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let sheet = spreadsheet
         .sheets
-        .as_ref()
-        .ok_or(Error::MissingSheet)?
-        .get(0)
+        .as_mut()
         .ok_or(Error::MissingSheet)?
+        .first_mut()
+        .ok_or(Error::MissingSheet)?;
+
+    let merges = sheet.merges.take().unwrap_or_default();
+
+    let grid_data = sheet
         .data
-        .as_ref()
+        .as_mut()
         .ok_or(Error::NotGridSheet)?
-        .get(0)
+        .first_mut()
         .ok_or(Error::NotGridSheet)?;
 
-    from_grid_data(grid_data)
+    if options.fill_merged_cells {
+        fill_merged_cells(grid_data, &merges);
+    }
+
+    from_grid_data_with_options(grid_data, options)
+}
+
+/// Takes the grid data of the sheet titled `title`, or of the first sheet,
+/// out of an already fetched or parsed spreadsheet. Useful when the rows are
+/// read with [`from_grid_data_with_options`] and the header row is needed as
+/// well, as the command line tools do.
+pub fn sheet_grid_data(spreadsheet: Spreadsheet, title: Option<&str>) -> Result<GridData> {
+    spreadsheet
+        .sheets
+        .unwrap_or_default()
+        .into_iter()
+        .find(|sheet| title.is_none() || sheet_title(sheet).map(String::as_str) == title)
+        .ok_or(Error::MissingSheet)?
+        .data
+        .and_then(|data| data.into_iter().next())
+        .ok_or(Error::NotGridSheet)
 }
 
 #[instrument(skip(grid_data))]
 pub fn from_grid_data<'a, T>(grid_data: &'a GridData) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    from_grid_data_with_options(grid_data, &Options::default())
+}
+
+// Renames the header of every declared column to its field name, so that
+// fields are matched by letter or by a header that differs from the field.
+// Letters name sheet columns, so they're offset by the grid's first column
+// and those left of the grid aren't found. Returns the expected format of
+// each declared column that was found.
+fn map_columns(
+    types: &mut smallmap::Map<usize, Option<&str>>,
+    columns: &[Column],
+    start_column: u32,
+) -> Result<Vec<(usize, ColumnFormat)>> {
+    let headers = types.clone();
+    let mut formats = Vec::new();
+
+    for column in columns {
+        let idx = match (column.letter, column.header) {
+            (Some(letter), _) => range::column_index(letter)
+                .and_then(|idx| idx.checked_sub(start_column))
+                .map(|idx| idx as usize),
+            (None, header) => {
+                let header = header.unwrap_or(column.field);
+                headers
+                    .iter()
+                    .filter(|(_, v)| *v == Some(header))
+                    .map(|(idx, _)| *idx)
+                    .min()
+            }
+        };
+
+        let idx = match idx {
+            Some(idx) => idx,
+            None if column.required => return Err(Error::MissingColumn(column.name().to_owned())),
+            None => continue,
+        };
+
+        set_column(types, idx, column.field);
+
+        if let Some(format) = column.format {
+            formats.push((idx, format));
+        }
+    }
+
+    Ok(formats)
+}
+
+// The values of the first ONE_OF_LIST data validation rule found below the
+// header of each column.
+fn dropdowns(grid_data: &GridData) -> Vec<(usize, Vec<&str>)> {
+    let mut dropdowns: Vec<(usize, Vec<&str>)> = Vec::new();
+
+    let rows = grid_data.row_data.iter().flatten().skip(1);
+    for values in rows.filter_map(|row| row.values.as_deref()) {
+        for (idx, cell) in values.iter().enumerate() {
+            let condition = cell
+                .data_validation
+                .as_ref()
+                .and_then(|rule| rule.condition.as_ref())
+                .filter(|condition| condition.type_.as_deref() == Some("ONE_OF_LIST"));
+
+            if let Some(condition) = condition {
+                if dropdowns.iter().all(|(other, _)| *other != idx) {
+                    let values = condition
+                        .values
+                        .iter()
+                        .flatten()
+                        .filter_map(|value| value.user_entered_value.as_deref())
+                        .collect();
+                    dropdowns.push((idx, values));
+                }
+            }
+        }
+    }
+
+    dropdowns
+}
+
+// The dropdown of a column, compared with the variants of the enum the
+// column is read into, whether the cell holds one value or a list of them.
+pub(crate) struct Dropdown<'de> {
+    column: &'de str,
+    values: Vec<&'de str>,
+}
+
+impl Dropdown<'_> {
+    pub(crate) fn check(&self, variants: &[&str]) -> Result<()> {
+        let missing_variants: Vec<_> = variants
+            .iter()
+            .filter(|variant| !self.values.contains(variant))
+            .map(|variant| variant.to_string())
+            .collect();
+        let unknown_values: Vec<_> = self
+            .values
+            .iter()
+            .filter(|value| !variants.contains(value))
+            .map(|value| value.to_string())
+            .collect();
+
+        if missing_variants.is_empty() && unknown_values.is_empty() {
+            return Ok(());
+        }
+
+        Err(Error::DropdownMismatch {
+            column: self.column.to_owned(),
+            missing_variants,
+            unknown_values,
+        })
+    }
+}
+
+// Reads the column at `idx` into `field`, and no other column: one whose
+// header happens to be the field's name is left out, or serde would see the
+// field twice. The column may lie beyond the last header cell, in which case
+// the gap is filled with blank headers.
+fn set_column<'de>(
+    types: &mut smallmap::Map<usize, Option<&'de str>>,
+    idx: usize,
+    field: &'de str,
+) {
+    for (other, header) in types.iter_mut() {
+        if *other != idx && *header == Some(field) {
+            *header = None;
+        }
+    }
+    for blank in types.len()..idx {
+        types.insert(blank, None);
+    }
+    types.insert(idx, Some(field));
+}
+
+#[instrument(skip(grid_data, options))]
+pub fn from_grid_data_with_options<'a, T>(grid_data: &'a GridData, options: &Options) -> Result<T>
 where
     T: Deserialize<'a>,
 {
@@ -64,28 +303,67 @@ where
         .as_deref()
         .ok_or(Error::ZeroRows)?
         .iter()
-        .map(|v| v.values.as_deref().expect("Values should be set"));
+        // Rows without any cells are sent without values.
+        .map(|v| v.values.as_deref().unwrap_or_default());
 
-    let types: smallmap::Map<_, _> = rows
-        .next()
-        .ok_or(Error::ZeroRows)?
+    let mut types = header::read_header(rows.next().ok_or(Error::ZeroRows)?, grid_data, options)?;
+
+    let start_column = grid_data.start_column.unwrap_or(0).try_into().unwrap_or(0);
+    let formats = map_columns(&mut types, &options.columns, start_column)?;
+
+    let dropdowns = match options.validate_dropdowns {
+        true => dropdowns(grid_data),
+        false => Vec::new(),
+    };
+
+    let hidden_rows = grid_data
+        .row_metadata
         .iter()
-        .map(|v| v.formatted_value.as_deref())
-        .enumerate()
+        .flatten()
+        .map(|row| {
+            (options.skip_hidden_rows && row.hidden_by_user == Some(true))
+                || (options.skip_filtered_rows && row.hidden_by_filter == Some(true))
+        })
         .collect();
 
     let mut deserializer = Deserializer {
         rows: rows.peekable(),
         types,
         key_idx: None,
+        start_row: grid_data.start_row.unwrap_or(0).try_into().unwrap_or(0),
+        start_column,
         row_idx: 1,
+        in_row: false,
         cur_type: None,
         parsing_enum: false,
+        options: options.clone(),
+        group_emitted: false,
+        group_pending: false,
+        in_group: false,
+        row_consumed: false,
+        formats,
+        headers_checked: false,
+        hidden_rows,
+        dropdowns,
     };
 
     T::deserialize(&mut deserializer)
 }
 
+/// The largest integer `n` such that `n` and `n + 1` are both exactly
+/// representable as f64 (2^53 - 1, like JavaScript's
+/// `Number.MAX_SAFE_INTEGER`). Sheets numbers beyond it may have been rounded.
+pub const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+// Removes thousands separators and whitespace from a formatted integer,
+// e.g. "1,234 567" becomes "1234567".
+fn strip_digit_grouping(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| *c != ',' && !c.is_whitespace())
+        .collect()
+}
+
 impl<'de, I> Deserializer<'de, I>
 where
     I: Iterator<Item = &'de [CellData]>,
@@ -108,6 +386,201 @@ where
             .and_then(|v| v.effective_value.as_ref())
     }
 
+    // Moves past the rows left out by the `skip_*_rows` options.
+    fn skip_rows(&mut self) {
+        while let Some(&row) = self.rows.peek() {
+            if !self.skips(row) {
+                break;
+            }
+
+            self.rows.next();
+            self.row_idx += 1;
+        }
+    }
+
+    fn skips(&self, row: &[CellData]) -> bool {
+        let options = &self.options;
+        let hidden = self.hidden_rows.get(self.row_idx as usize) == Some(&true);
+        let blank = row.iter().all(|cell| cell.effective_value.is_none());
+        let comment = row
+            .first()
+            .and_then(|cell| cell.formatted_value.as_deref())
+            .is_some_and(|value| value.starts_with('#'));
+
+        hidden || (options.skip_blank_rows && blank) || (options.skip_comment_rows && comment)
+    }
+
+    // The 1-based row number of the current row as shown in the sheet.
+    fn sheet_row(&self) -> u32 {
+        self.start_row + self.row_idx + 1
+    }
+
+    // Points fields given by position, through `Options::positions` or by a
+    // name such as `"$C"`, at their column instead of a header. Positions
+    // are sheet columns, so those left of the grid aren't read.
+    fn map_positions(&mut self, fields: &[&'static str]) -> Result<()> {
+        for &field in fields {
+            let position = match self.options.positions.get(field) {
+                Some(position) => range::column_position(position)
+                    .ok_or_else(|| Error::InvalidPosition(position.clone()))?,
+                None => match range::column_position(field) {
+                    Some(position) => position,
+                    None => continue,
+                },
+            };
+            let idx = match position.checked_sub(self.start_column as usize) {
+                Some(idx) => idx,
+                None => continue,
+            };
+
+            set_column(&mut self.types, idx, field);
+        }
+
+        Ok(())
+    }
+
+    // Checks the header row against the fields of the outermost row struct.
+    // The children field of a grouped row isn't a column, and the columns
+    // of the child rows aren't known here, so with grouping other columns
+    // aren't reported as unexpected.
+    fn check_headers(&self, fields: &[&str]) -> Result<()> {
+        let mut headers: Vec<_> = self
+            .types
+            .iter()
+            .filter_map(|(idx, header)| header.map(|header| (*idx, header)))
+            .collect();
+        headers.sort_unstable();
+        let headers: Vec<_> = headers.into_iter().map(|(_, header)| header).collect();
+
+        let children = self.options.grouping.as_ref().map(|g| g.children.as_str());
+        let fields: Vec<_> = fields
+            .iter()
+            .copied()
+            .filter(|field| Some(*field) != children)
+            .collect();
+
+        header::check_fields(&headers, &fields, children.is_none())
+    }
+
+    // Takes the dropdown of the current column, so that it's compared with
+    // the variants of the enum the column is read into only once.
+    fn take_dropdown(&mut self) -> Option<Dropdown<'de>> {
+        let idx = self.key_idx?;
+        let position = self.dropdowns.iter().position(|(other, _)| *other == idx)?;
+
+        Some(Dropdown {
+            column: self.cur_type.unwrap_or_default(),
+            values: self.dropdowns.swap_remove(position).1,
+        })
+    }
+
+    // Rejects a non-blank cell in a column declared with a different number
+    // format. Plain text cells carry no number format.
+    fn check_format(&mut self) -> Result<()> {
+        let expected = match self
+            .formats
+            .iter()
+            .find(|(idx, _)| Some(*idx) == self.key_idx)
+        {
+            Some((_, expected)) => *expected,
+            None => return Ok(()),
+        };
+
+        let cell = match self.get_cur_cell_data() {
+            Some(cell) if cell.effective_value.is_some() => cell,
+            _ => return Ok(()),
+        };
+
+        let found = cell
+            .effective_format
+            .as_ref()
+            .and_then(|f| f.number_format.as_ref())
+            .and_then(|f| f.type_.as_deref());
+
+        match found {
+            Some(found) if found == expected.as_str() => Ok(()),
+            None if expected == ColumnFormat::Text => Ok(()),
+            _ => Err(Error::UnexpectedFormat {
+                column: self.cur_type.unwrap_or_default().to_owned(),
+                row: self.sheet_row(),
+                expected,
+                found: found.map(str::to_owned),
+            }),
+        }
+    }
+
+    // Deserializes the current row as a whole and moves on to the next one.
+    fn deserialize_row<T>(&mut self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.key_idx = None;
+        self.cur_type = None;
+        self.group_emitted = false;
+        self.in_row = true;
+
+        let val = seed.deserialize(&mut *self);
+
+        self.in_row = false;
+
+        if !std::mem::take(&mut self.row_consumed) {
+            self.rows.next();
+            self.row_idx += 1;
+        }
+
+        val
+    }
+
+    fn column_index(&self, name: &str) -> Result<usize> {
+        self.types
+            .iter()
+            .filter(|(_, header)| *header == Some(name))
+            .map(|(idx, _)| *idx)
+            .min()
+            .ok_or_else(|| Error::MissingColumn(name.to_owned()))
+    }
+
+    // Hands out the children field of a grouped parent row once all of its
+    // columns have been visited.
+    fn next_group_key<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let children = match &self.options.grouping {
+            Some(grouping) if !self.in_group && !self.group_emitted => grouping.children.clone(),
+            _ => return Ok(None),
+        };
+
+        self.group_emitted = true;
+        self.group_pending = true;
+
+        seed.deserialize(children.into_deserializer()).map(Some)
+    }
+
+    // Whether the next row continues the group whose key columns hold `keys`,
+    // i.e. each key column is either blank or equal to the parent's value.
+    fn continues_group(&mut self, keys: &[(usize, Option<&'de str>)]) -> bool {
+        let row = match self.rows.peek() {
+            Some(row) => *row,
+            None => return false,
+        };
+
+        if row.iter().all(|cell| cell.effective_value.is_none()) {
+            return false;
+        }
+
+        keys.iter().all(|(idx, key)| {
+            match row
+                .get(*idx)
+                .and_then(|cell| cell.formatted_value.as_deref())
+                .filter(|value| !value.is_empty())
+            {
+                None => true,
+                value => value == *key,
+            }
+        })
+    }
+
     fn deserialize_number(&mut self) -> Result<f64> {
         let effective_value =
             self.get_cur_effective_value()
@@ -115,7 +588,7 @@ where
                     "Key idx: {:?}, Row idx {:?}, Next {:?}, Types: {:?}",
                     self.key_idx,
                     self.row_idx,
-                    self.rows.peek().and_then(|row| row.get(0)),
+                    self.rows.peek().and_then(|row| row.first()),
                     self.types
                 )))?;
 
@@ -126,6 +599,65 @@ where
         Ok(value)
     }
 
+    // Integers wider than an f64 mantissa are usually stored as text, so text
+    // cells are parsed directly. Number cells must hold a whole number.
+    fn deserialize_integer<T>(&mut self, ty: &'static str) -> Result<T>
+    where
+        T: FromStr<Err = ParseIntError>,
+    {
+        let effective_value = self.get_cur_effective_value();
+        let text = match effective_value.and_then(|v| v.number_value) {
+            Some(number) if self.options.integers_from_text => {
+                self.check_precision(number)?;
+                strip_digit_grouping(self.deserialize_formatted_value()?)
+            }
+            Some(number) if number.fract() != 0.0 || !number.is_finite() => {
+                return Err(Error::NotInteger(number.to_string()));
+            }
+            Some(number) => {
+                self.check_precision(number)?;
+                format!("{:.0}", number)
+            }
+            None if self.options.integers_from_text => {
+                strip_digit_grouping(self.deserialize_formatted_value()?)
+            }
+            None => self.deserialize_formatted_value()?.trim().to_owned(),
+        };
+
+        // Unsigned types report negative numbers as an invalid digit.
+        let negative = text
+            .strip_prefix('-')
+            .is_some_and(|digits| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()));
+
+        text.parse().map_err(|err: ParseIntError| match err.kind() {
+            IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+                Error::IntegerOutOfRange { value: text, ty }
+            }
+            IntErrorKind::InvalidDigit if negative => Error::IntegerOutOfRange { value: text, ty },
+            _ => Error::NotInteger(text),
+        })
+    }
+
+    // Sheets stores numbers as f64, so integers beyond 2^53 may have been
+    // rounded when they were entered. Those are only trusted when the
+    // formatted text shows exactly the same integer.
+    fn check_precision(&mut self, number: f64) -> Result<()> {
+        if number.abs() <= MAX_SAFE_INTEGER {
+            return Ok(());
+        }
+
+        let formatted = self
+            .get_cur_cell_data()
+            .and_then(|v| v.formatted_value.as_deref())
+            .map(strip_digit_grouping);
+
+        match formatted {
+            Some(text) if text.parse::<i128>().ok() == Some(number as i128) => Ok(()),
+            Some(text) => Err(Error::PrecisionLoss(text)),
+            None => Err(Error::PrecisionLoss(number.to_string())),
+        }
+    }
+
     fn deserialize_bool(&mut self) -> Result<bool> {
         let value = self
             .get_cur_effective_value()
@@ -133,7 +665,7 @@ where
                 "Key idx: {:?}, Row idx {:?}, Next {:?}, Types: {:?}",
                 self.key_idx,
                 self.row_idx,
-                self.rows.peek().and_then(|row| row.get(0)),
+                self.rows.peek().and_then(|row| row.first()),
                 self.types
             )))?
             .bool_value
@@ -142,13 +674,22 @@ where
         Ok(value)
     }
 
+    fn deserialize_encoded_bytes(&mut self) -> Result<Vec<u8>> {
+        let value = self.deserialize_formatted_value()?;
+
+        self.options
+            .bytes_encoding
+            .decode(value)
+            .ok_or_else(|| Error::NotBytes(self.options.bytes_encoding, value.to_owned()))
+    }
+
     fn deserialize_formatted_value(&mut self) -> Result<&'de str> {
         self.get_cur_cell_data()
             .ok_or(Error::MissingValue(format!(
                 "Key idx: {:?}, Row idx {:?}, Next {:?}, Types: {:?}",
                 self.key_idx,
                 self.row_idx,
-                self.rows.peek().and_then(|row| row.get(0)),
+                self.rows.peek().and_then(|row| row.first()),
                 self.types
             )))?
             .formatted_value
@@ -157,13 +698,13 @@ where
                 "Key idx: {:?}, Row idx {:?}, Next {:?}, Types: {:?}",
                 self.key_idx,
                 self.row_idx,
-                self.rows.peek().and_then(|row| row.get(0)),
+                self.rows.peek().and_then(|row| row.first()),
                 self.types
             )))
     }
 }
 
-impl<'de, 'a, I> de::Deserializer<'de> for &'a mut Deserializer<'de, I>
+impl<'de, I> de::Deserializer<'de> for &mut Deserializer<'de, I>
 where
     I: Iterator<Item = &'de [CellData]>,
 {
@@ -256,7 +797,7 @@ where
     where
         V: Visitor<'de>,
     {
-        visitor.visit_i64(self.deserialize_number()? as i64)
+        visitor.visit_i64(self.deserialize_integer("i64")?)
     }
 
     fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value>
@@ -284,7 +825,7 @@ where
     where
         V: Visitor<'de>,
     {
-        visitor.visit_u64(self.deserialize_number()? as u64)
+        visitor.visit_u64(self.deserialize_integer("u64")?)
     }
 
     fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value>
@@ -301,11 +842,31 @@ where
         visitor.visit_f64(self.deserialize_number()?)
     }
 
-    fn deserialize_char<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_i128(self.deserialize_integer("i128")?)
+    }
+
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_u128(self.deserialize_integer("u128")?)
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        Err(de::Error::custom("Char type not supported"))
+        let value = self.deserialize_formatted_value()?;
+        let mut chars = value.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => visitor.visit_char(c),
+            _ => Err(Error::NotChar(value.to_owned())),
+        }
     }
 
     fn deserialize_str<V>(self, visitor: V) -> Result<V::Value>
@@ -319,7 +880,7 @@ where
                 "Key idx: {:?}, Row idx {:?}, Next {:?}, Types: {:?}",
                 self.key_idx,
                 self.row_idx,
-                self.rows.peek().and_then(|row| row.get(0)),
+                self.rows.peek().and_then(|row| row.first()),
                 self.types
             )))?;
 
@@ -333,18 +894,18 @@ where
         self.deserialize_str(visitor)
     }
 
-    fn deserialize_bytes<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        Err(de::Error::custom("Bytes type not supported"))
+        visitor.visit_byte_buf(self.deserialize_encoded_bytes()?)
     }
 
-    fn deserialize_byte_buf<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        Err(de::Error::custom("Byte buf type not supported"))
+        self.deserialize_bytes(visitor)
     }
 
     fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
@@ -398,14 +959,55 @@ where
     where
         V: Visitor<'de>,
     {
+        if self.key_idx.is_some() {
+            let value = self
+                .get_cur_cell_data()
+                .and_then(|v| v.formatted_value.as_deref())
+                .unwrap_or_default();
+
+            let mut dropdown = self.take_dropdown();
+            let value = visitor.visit_seq(CellItems::new(value, &mut dropdown, &self.options));
+
+            // A list without enum items, such as an empty cell, leaves the
+            // dropdown to be checked by a later row.
+            if let (Some(idx), Some(dropdown)) = (self.key_idx, dropdown) {
+                self.dropdowns.push((idx, dropdown.values));
+            }
+
+            return value;
+        }
+
+        // A sheet without data rows never reaches a row struct, so its
+        // header row can only be checked against declared columns.
+        if !self.in_row && self.options.validate_headers && !self.headers_checked {
+            self.skip_rows();
+            if self.rows.peek().is_none() && !self.options.columns.is_empty() {
+                let fields: Vec<_> = self.options.columns.iter().map(|c| c.field).collect();
+                self.map_positions(&fields)?;
+                self.headers_checked = true;
+                self.check_headers(&fields)?;
+            }
+        }
+
         let value = visitor.visit_seq(self)?;
         Ok(value)
     }
 
-    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
+        // With `bytes_arrays`, fixed-size arrays such as `[u8; 32]` are
+        // stored encoded in a single cell rather than as a delimited list.
+        if self.key_idx.is_some() && self.options.bytes_arrays {
+            let bytes = self.deserialize_encoded_bytes()?;
+            if bytes.len() != len {
+                return Err(de::Error::invalid_length(bytes.len(), &visitor));
+            }
+
+            return visitor.visit_seq(SeqDeserializer::new(bytes.into_iter()));
+        }
+
         self.deserialize_seq(visitor)
     }
 
@@ -425,30 +1027,67 @@ where
     where
         V: Visitor<'de>,
     {
+        if !self.in_row {
+            return visitor.visit_map(KeyedRows::new(self)?);
+        }
+
         visitor.visit_map(self)
     }
 
     fn deserialize_struct<V>(
         self,
-        _name: &'static str,
-        _fields: &'static [&'static str],
+        name: &'static str,
+        fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
+        // A struct on its own is read from the first row; only maps key the
+        // rows of the whole sheet.
+        if !self.in_row {
+            self.skip_rows();
+            if self.rows.peek().is_none() {
+                return Err(Error::Eof);
+            }
+
+            self.key_idx = None;
+            self.cur_type = None;
+            self.group_emitted = false;
+            self.in_row = true;
+
+            let val = (&mut *self).deserialize_struct(name, fields, visitor);
+
+            self.in_row = false;
+
+            return val;
+        }
+
+        if self.in_row && self.key_idx.is_none() {
+            self.map_positions(fields)?;
+
+            if self.options.validate_headers && !self.headers_checked {
+                self.headers_checked = true;
+                self.check_headers(fields)?;
+            }
+        }
+
         self.deserialize_map(visitor)
     }
 
     fn deserialize_enum<V>(
         self,
         _name: &'static str,
-        _variants: &'static [&'static str],
+        variants: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
+        if let Some(dropdown) = self.take_dropdown() {
+            dropdown.check(variants)?;
+        }
+
         visitor.visit_enum(Enum::new(self))
     }
 
@@ -464,7 +1103,7 @@ where
                     "Key idx: {:?}, Row idx {:?}, Next {:?}, Types: {:?}",
                     self.key_idx,
                     self.row_idx,
-                    self.rows.peek().and_then(|row| row.get(0)),
+                    self.rows.peek().and_then(|row| row.first()),
                     self.types
                 )))?;
 
@@ -474,7 +1113,7 @@ where
                 "Key idx: {:?}, Row idx {:?}, Next {:?}, Types: {:?}",
                 self.key_idx,
                 self.row_idx,
-                self.rows.peek().and_then(|row| row.get(0)),
+                self.rows.peek().and_then(|row| row.first()),
                 self.types
             )))?)
         }
@@ -498,19 +1137,22 @@ where
     where
         K: DeserializeSeed<'de>,
     {
+        if self.group_emitted && !self.in_group {
+            return Ok(None);
+        }
+
         let mut new_idx = match self.key_idx {
             None => 0,
             Some(i) => i + 1,
         };
 
-        while self.types.get(&new_idx).map(|v| v.is_some()).is_none()
-            && new_idx < self.types.len() - 1
-        {
+        // Columns with a blank header aren't fields.
+        while !matches!(self.types.get(&new_idx), Some(Some(_))) && new_idx + 1 < self.types.len() {
             new_idx += 1;
         }
 
         if new_idx >= self.get_cur_row_data().len() {
-            return Ok(None);
+            return self.next_group_key(seed);
         }
 
         match self.types.get(&new_idx) {
@@ -518,9 +1160,11 @@ where
                 self.key_idx = Some(new_idx);
                 self.cur_type = Some(v);
 
-                seed.deserialize(&mut *self).map(Some)
+                // Keys are the header text, whether the row is read into a
+                // struct or into a map such as `HashMap<String, String>`.
+                seed.deserialize(BorrowedStrDeserializer::new(v)).map(Some)
             }
-            _ => Ok(None),
+            _ => self.next_group_key(seed),
         }
     }
 
@@ -528,6 +1172,13 @@ where
     where
         V: DeserializeSeed<'de>,
     {
+        if self.group_pending {
+            self.group_pending = false;
+            return seed.deserialize(Group::new(self)?);
+        }
+
+        self.check_format()?;
+
         seed.deserialize(self)
     }
 }
@@ -542,19 +1193,13 @@ where
     where
         T: DeserializeSeed<'de>,
     {
+        self.skip_rows();
+
         if self.rows.peek().is_none() {
             return Ok(None);
         }
 
-        self.key_idx = None;
-        self.cur_type = None;
-
-        let val = seed.deserialize(&mut *self).map(Some);
-
-        self.rows.next();
-        self.row_idx += 1;
-
-        val
+        self.deserialize_row(seed).map(Some)
     }
 
     fn size_hint(&self) -> Option<usize> {
@@ -562,50 +1207,287 @@ where
     }
 }
 
-struct Enum<'a, 'de: 'a, I>
+// The whole sheet as a map from the key column to the remaining row, e.g. a
+// `HashMap<String, Row>`.
+struct KeyedRows<'a, 'de, I>
 where
     I: Iterator<Item = &'de [CellData]>,
 {
     de: &'a mut Deserializer<'de, I>,
+    key_idx: usize,
+    seen: HashMap<&'de str, u32>,
 }
 
-impl<'a, 'de, I> Enum<'a, 'de, I>
+impl<'a, 'de, I> KeyedRows<'a, 'de, I>
 where
     I: Iterator<Item = &'de [CellData]>,
 {
-    fn new(de: &'a mut Deserializer<'de, I>) -> Self {
-        Enum { de }
+    fn new(de: &'a mut Deserializer<'de, I>) -> Result<Self> {
+        let key_idx = match de.options.map_key.clone() {
+            Some(name) => de.column_index(&name)?,
+            None => de
+                .types
+                .iter()
+                .filter(|(_, header)| header.is_some())
+                .map(|(idx, _)| *idx)
+                .min()
+                .ok_or(Error::ZeroRows)?,
+        };
+
+        Ok(KeyedRows {
+            de,
+            key_idx,
+            seen: HashMap::new(),
+        })
     }
 }
 
-impl<'a, 'de, I> EnumAccess<'de> for Enum<'a, 'de, I>
+impl<'de, I> MapAccess<'de> for KeyedRows<'_, 'de, I>
 where
     I: Iterator<Item = &'de [CellData]>,
 {
     type Error = Error;
 
-    type Variant = Self;
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        self.de.skip_rows();
 
-    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+        // Completely blank rows don't have a key and are left out.
+        while let Some(row) = self.de.rows.peek() {
+            if row.iter().any(|cell| cell.effective_value.is_some()) {
+                break;
+            }
+
+            self.de.rows.next();
+            self.de.row_idx += 1;
+        }
+
+        if self.de.rows.peek().is_none() {
+            return Ok(None);
+        }
+
+        self.de.key_idx = Some(self.key_idx);
+        self.de.cur_type = self.de.types.get(&self.key_idx).copied().flatten();
+
+        let key = self.de.deserialize_formatted_value()?;
+        let row = self.de.sheet_row();
+
+        if let Some(first_row) = self.seen.insert(key, row) {
+            return Err(Error::DuplicateKey {
+                key: key.to_owned(),
+                first_row,
+                second_row: row,
+            });
+        }
+
+        self.de.in_row = true;
+        let val = seed.deserialize(&mut *self.de).map(Some);
+        self.de.in_row = false;
+
+        val
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
     where
         V: DeserializeSeed<'de>,
     {
-        self.de.parsing_enum = true;
-        let val = seed.deserialize(&mut *self.de)?;
-        self.de.parsing_enum = false;
-
-        Ok((val, self))
+        self.de.deserialize_row(seed)
     }
 }
 
-impl<'de, 'a, I> VariantAccess<'de> for Enum<'a, 'de, I>
+// The child rows of a grouped parent: the parent's own row followed by every
+// consecutive row that continues its key columns. Deserializes as a sequence
+// of row-level values.
+struct Group<'a, 'de, I>
 where
     I: Iterator<Item = &'de [CellData]>,
 {
-    type Error = Error;
+    de: &'a mut Deserializer<'de, I>,
+    keys: Vec<(usize, Option<&'de str>)>,
+    started: bool,
+}
 
-    fn unit_variant(self) -> Result<()> {
-        Ok(())
+impl<'a, 'de, I> Group<'a, 'de, I>
+where
+    I: Iterator<Item = &'de [CellData]>,
+{
+    fn new(de: &'a mut Deserializer<'de, I>) -> Result<Self> {
+        let key_columns = de
+            .options
+            .grouping
+            .as_ref()
+            .map(|grouping| grouping.key_columns.clone())
+            .unwrap_or_default();
+
+        let row = de.get_cur_row_data();
+        let keys = key_columns
+            .iter()
+            .map(|name| {
+                let idx = de.column_index(name)?;
+                let key = row
+                    .get(idx)
+                    .and_then(|cell| cell.formatted_value.as_deref());
+
+                Ok((idx, key))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Group {
+            de,
+            keys,
+            started: false,
+        })
+    }
+}
+
+impl<'de, I> de::Deserializer<'de> for Group<'_, 'de, I>
+where
+    I: Iterator<Item = &'de [CellData]>,
+{
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de, I> SeqAccess<'de> for Group<'_, 'de, I>
+where
+    I: Iterator<Item = &'de [CellData]>,
+{
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if self.started {
+            self.de.rows.next();
+            self.de.row_idx += 1;
+            self.de.row_consumed = true;
+            self.de.skip_rows();
+
+            if !self.de.continues_group(&self.keys) {
+                return Ok(None);
+            }
+        }
+
+        self.started = true;
+        self.de.key_idx = None;
+        self.de.cur_type = None;
+        self.de.in_group = true;
+
+        let val = seed.deserialize(&mut *self.de).map(Some);
+
+        self.de.in_group = false;
+
+        val
+    }
+}
+
+// Items of a single cell split on the configured delimiter, e.g. the values of
+// a multi-select dropdown.
+struct CellItems<'a, 'de> {
+    items: Option<std::str::Split<'de, char>>,
+    dropdown: &'a mut Option<Dropdown<'de>>,
+    options: &'a Options,
+}
+
+impl<'a, 'de> CellItems<'a, 'de> {
+    fn new(value: &'de str, dropdown: &'a mut Option<Dropdown<'de>>, options: &'a Options) -> Self {
+        // An empty cell is an empty sequence, not a sequence of one empty item.
+        let items = (!value.trim().is_empty()).then(|| value.split(options.delimiter));
+
+        CellItems {
+            items,
+            dropdown,
+            options,
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for CellItems<'_, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        while let Some(item) = self.items.as_mut().and_then(Iterator::next) {
+            let item = if self.options.trim_items {
+                item.trim()
+            } else {
+                item
+            };
+
+            if item.is_empty() && self.options.empty_items == EmptyItems::Skip {
+                continue;
+            }
+
+            return seed
+                .deserialize(TextDeserializer::new(item, self.dropdown))
+                .map(Some);
+        }
+
+        Ok(None)
+    }
+}
+
+struct Enum<'a, 'de: 'a, I>
+where
+    I: Iterator<Item = &'de [CellData]>,
+{
+    de: &'a mut Deserializer<'de, I>,
+}
+
+impl<'a, 'de, I> Enum<'a, 'de, I>
+where
+    I: Iterator<Item = &'de [CellData]>,
+{
+    fn new(de: &'a mut Deserializer<'de, I>) -> Self {
+        Enum { de }
+    }
+}
+
+impl<'a, 'de, I> EnumAccess<'de> for Enum<'a, 'de, I>
+where
+    I: Iterator<Item = &'de [CellData]>,
+{
+    type Error = Error;
+
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.de.parsing_enum = true;
+        let val = seed.deserialize(&mut *self.de)?;
+        self.de.parsing_enum = false;
+
+        Ok((val, self))
+    }
+}
+
+impl<'de, 'a, I> VariantAccess<'de> for Enum<'a, 'de, I>
+where
+    I: Iterator<Item = &'de [CellData]>,
+{
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
     }
 
     fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
@@ -631,7 +1513,7 @@ where
 }
 
 #[cfg(test)]
-fn string_cell(s: &str) -> CellData {
+pub(crate) fn string_cell(s: &str) -> CellData {
     CellData {
         formatted_value: Some(s.to_owned()),
         effective_value: Some(ExtendedValue {
@@ -643,7 +1525,19 @@ fn string_cell(s: &str) -> CellData {
 }
 
 #[cfg(test)]
-fn grid_data(cells: Vec<Vec<CellData>>) -> GridData {
+pub(crate) fn number_cell(n: f64) -> CellData {
+    CellData {
+        formatted_value: Some(n.to_string()),
+        effective_value: Some(ExtendedValue {
+            number_value: Some(n),
+            ..ExtendedValue::default()
+        }),
+        ..CellData::default()
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn grid_data(cells: Vec<Vec<CellData>>) -> GridData {
     GridData {
         row_data: Some(
             cells
@@ -704,3 +1598,1103 @@ fn test_empty() {
 
     assert_eq!(expected, result)
 }
+
+#[test]
+fn test_empty_header_row() {
+    let mut data = grid_data(vec![vec![], vec![string_cell("v1")]]);
+    data.row_data.as_mut().unwrap()[0].values = None;
+
+    let result: Vec<HashMap<String, String>> = from_grid_data(&data).unwrap();
+
+    assert_eq!(vec![HashMap::new()], result);
+}
+
+#[test]
+fn test_cell_sequence() {
+    use std::collections::HashSet;
+
+    #[derive(Deserialize, PartialEq, Eq, Hash, Debug)]
+    enum Colour {
+        Red,
+        Green,
+        Blue,
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Test {
+        tags: Vec<String>,
+        colours: HashSet<Colour>,
+        sizes: Vec<u32>,
+    }
+
+    let data = grid_data(vec![
+        vec![
+            string_cell("tags"),
+            string_cell("colours"),
+            string_cell("sizes"),
+        ],
+        vec![
+            string_cell("a, b ,,c"),
+            string_cell("Red, Blue"),
+            string_cell("1,2"),
+        ],
+        vec![string_cell("a"), string_cell("Green"), CellData::default()],
+    ]);
+
+    let expected = vec![
+        Test {
+            tags: vec!["a".to_owned(), "b".to_owned(), "c".to_owned()],
+            colours: HashSet::from([Colour::Red, Colour::Blue]),
+            sizes: vec![1, 2],
+        },
+        Test {
+            tags: vec!["a".to_owned()],
+            colours: HashSet::from([Colour::Green]),
+            sizes: vec![],
+        },
+    ];
+
+    let result: Vec<Test> = from_grid_data(&data).unwrap();
+
+    assert_eq!(expected, result)
+}
+
+#[test]
+fn test_cell_sequence_options() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Test {
+        tags: Vec<String>,
+    }
+
+    let data = grid_data(vec![
+        vec![string_cell("tags")],
+        vec![string_cell("a; b;;c")],
+    ]);
+
+    let options = Options {
+        delimiter: ';',
+        trim_items: false,
+        empty_items: EmptyItems::Keep,
+        ..Options::default()
+    };
+
+    let expected = vec![Test {
+        tags: vec![
+            "a".to_owned(),
+            " b".to_owned(),
+            "".to_owned(),
+            "c".to_owned(),
+        ],
+    }];
+
+    let result: Vec<Test> = from_grid_data_with_options(&data, &options).unwrap();
+
+    assert_eq!(expected, result)
+}
+
+#[test]
+fn test_merged_cells() {
+    use google_sheets4::api::GridRange;
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Test {
+        category: String,
+        item: String,
+    }
+
+    let mut data = grid_data(vec![
+        vec![string_cell("category"), string_cell("item")],
+        vec![string_cell("Fruit"), string_cell("Apple")],
+        vec![CellData::default(), string_cell("Pear")],
+        vec![string_cell("Veg"), string_cell("Leek")],
+    ]);
+
+    fill_merged_cells(
+        &mut data,
+        &[GridRange {
+            start_row_index: Some(1),
+            end_row_index: Some(3),
+            start_column_index: Some(0),
+            end_column_index: Some(1),
+            ..GridRange::default()
+        }],
+    );
+
+    let expected = vec![
+        Test {
+            category: "Fruit".to_owned(),
+            item: "Apple".to_owned(),
+        },
+        Test {
+            category: "Fruit".to_owned(),
+            item: "Pear".to_owned(),
+        },
+        Test {
+            category: "Veg".to_owned(),
+            item: "Leek".to_owned(),
+        },
+    ];
+
+    let result: Vec<Test> = from_grid_data(&data).unwrap();
+
+    assert_eq!(expected, result)
+}
+
+#[test]
+fn test_grouping() {
+    use crate::options::Grouping;
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Item {
+        product: String,
+        quantity: u32,
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Order {
+        order: String,
+        customer: String,
+        items: Vec<Item>,
+    }
+
+    let data = grid_data(vec![
+        vec![
+            string_cell("order"),
+            string_cell("customer"),
+            string_cell("product"),
+            string_cell("quantity"),
+        ],
+        vec![
+            string_cell("A1"),
+            string_cell("Alice"),
+            string_cell("Apple"),
+            number_cell(2.0),
+        ],
+        vec![
+            CellData::default(),
+            CellData::default(),
+            string_cell("Pear"),
+            number_cell(1.0),
+        ],
+        vec![
+            string_cell("A1"),
+            string_cell("Alice"),
+            string_cell("Plum"),
+            number_cell(4.0),
+        ],
+        vec![
+            string_cell("B2"),
+            string_cell("Bob"),
+            string_cell("Leek"),
+            number_cell(3.0),
+        ],
+    ]);
+
+    let options = Options {
+        grouping: Some(Grouping {
+            key_columns: vec!["order".to_owned()],
+            children: "items".to_owned(),
+        }),
+        ..Options::default()
+    };
+
+    let item = |product: &str, quantity| Item {
+        product: product.to_owned(),
+        quantity,
+    };
+
+    let expected = vec![
+        Order {
+            order: "A1".to_owned(),
+            customer: "Alice".to_owned(),
+            items: vec![item("Apple", 2), item("Pear", 1), item("Plum", 4)],
+        },
+        Order {
+            order: "B2".to_owned(),
+            customer: "Bob".to_owned(),
+            items: vec![item("Leek", 3)],
+        },
+    ];
+
+    let result: Vec<Order> = from_grid_data_with_options(&data, &options).unwrap();
+
+    assert_eq!(expected, result)
+}
+
+#[test]
+fn test_keyed_map() {
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Test {
+        name: String,
+        price: f64,
+    }
+
+    let data = grid_data(vec![
+        vec![string_cell("name"), string_cell("id"), string_cell("price")],
+        vec![string_cell("Apple"), number_cell(7.0), number_cell(0.5)],
+        vec![
+            CellData::default(),
+            CellData::default(),
+            CellData::default(),
+        ],
+        vec![string_cell("Pear"), number_cell(3.0), number_cell(0.75)],
+    ]);
+
+    let test = |name: &str, price| Test {
+        name: name.to_owned(),
+        price,
+    };
+
+    let by_name: HashMap<String, Test> = from_grid_data(&data).unwrap();
+
+    assert_eq!(
+        HashMap::from([
+            ("Apple".to_owned(), test("Apple", 0.5)),
+            ("Pear".to_owned(), test("Pear", 0.75)),
+        ]),
+        by_name
+    );
+
+    let options = Options {
+        map_key: Some("id".to_owned()),
+        ..Options::default()
+    };
+
+    let by_id: BTreeMap<u32, Test> = from_grid_data_with_options(&data, &options).unwrap();
+
+    assert_eq!(
+        BTreeMap::from([(3, test("Pear", 0.75)), (7, test("Apple", 0.5))]),
+        by_id
+    );
+
+    let ordered: indexmap::IndexMap<u32, Test> =
+        from_grid_data_with_options(&data, &options).unwrap();
+
+    assert_eq!(vec![&7, &3], ordered.keys().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_struct_reads_first_row() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Test {
+        col1: String,
+        col2: String,
+    }
+
+    let data = grid_data(vec![
+        vec![string_cell("col1"), string_cell("col2")],
+        vec![string_cell("a"), string_cell("b")],
+    ]);
+
+    assert_eq!(
+        Test {
+            col1: "a".to_owned(),
+            col2: "b".to_owned(),
+        },
+        from_grid_data(&data).unwrap()
+    );
+}
+
+#[test]
+fn test_keyed_map_duplicate_key() {
+    use std::collections::HashMap;
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Test {
+        name: String,
+    }
+
+    let data = grid_data(vec![
+        vec![string_cell("name")],
+        vec![string_cell("Apple")],
+        vec![string_cell("Pear")],
+        vec![string_cell("Apple")],
+    ]);
+
+    let result: Result<HashMap<String, Test>> = from_grid_data(&data);
+
+    match result {
+        Err(Error::DuplicateKey {
+            key,
+            first_row,
+            second_row,
+        }) => assert_eq!(("Apple", 2, 4), (key.as_str(), first_row, second_row)),
+        other => panic!("expected duplicate key error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_bytes() {
+    use crate::options::BytesEncoding;
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Test {
+        #[serde(with = "serde_bytes")]
+        token: Vec<u8>,
+        buf: serde_bytes::ByteBuf,
+        hash: [u8; 4],
+    }
+
+    let data = grid_data(vec![
+        vec![
+            string_cell("token"),
+            string_cell("buf"),
+            string_cell("hash"),
+        ],
+        vec![
+            string_cell("AQID"),
+            string_cell("/w=="),
+            string_cell("3q2+7w=="),
+        ],
+    ]);
+
+    let expected = vec![Test {
+        token: vec![1, 2, 3],
+        buf: serde_bytes::ByteBuf::from(vec![255]),
+        hash: [0xde, 0xad, 0xbe, 0xef],
+    }];
+
+    let options = Options {
+        bytes_arrays: true,
+        ..Options::default()
+    };
+
+    let result: Vec<Test> = from_grid_data_with_options(&data, &options).unwrap();
+
+    assert_eq!(expected, result);
+
+    let data = grid_data(vec![
+        vec![
+            string_cell("token"),
+            string_cell("buf"),
+            string_cell("hash"),
+        ],
+        vec![
+            string_cell("010203"),
+            string_cell("ff"),
+            string_cell("DEADBEEF"),
+        ],
+    ]);
+
+    let options = Options {
+        bytes_encoding: BytesEncoding::Hex,
+        bytes_arrays: true,
+        ..Options::default()
+    };
+
+    let result: Vec<Test> = from_grid_data_with_options(&data, &options).unwrap();
+
+    assert_eq!(expected, result);
+
+    // Without `bytes_arrays`, arrays are delimited lists whatever the cell
+    // happens to decode to.
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Words {
+        words: [String; 3],
+    }
+
+    let data = grid_data(vec![vec![string_cell("words")], vec![string_cell("abcd")]]);
+    let err = from_grid_data::<Vec<Words>>(&data).unwrap_err();
+
+    assert!(err.to_string().contains("invalid length 1"), "{}", err);
+
+    let data = grid_data(vec![vec![string_cell("token")], vec![string_cell("xyz")]]);
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Token {
+        #[serde(with = "serde_bytes")]
+        token: Vec<u8>,
+    }
+
+    let result: Result<Vec<Token>> = from_grid_data_with_options(&data, &options);
+
+    assert!(matches!(
+        result,
+        Err(Error::NotBytes(BytesEncoding::Hex, _))
+    ));
+}
+
+#[test]
+fn test_char_and_wide_integers() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Test {
+        grade: char,
+        id: u128,
+        delta: i128,
+    }
+
+    let data = grid_data(vec![
+        vec![
+            string_cell("grade"),
+            string_cell("id"),
+            string_cell("delta"),
+        ],
+        vec![
+            string_cell("A"),
+            string_cell("340282366920938463463374607431768211455"),
+            number_cell(-12.0),
+        ],
+    ]);
+
+    let expected = vec![Test {
+        grade: 'A',
+        id: u128::MAX,
+        delta: -12,
+    }];
+
+    let result: Vec<Test> = from_grid_data(&data).unwrap();
+
+    assert_eq!(expected, result);
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Id {
+        id: u128,
+    }
+
+    let data = grid_data(vec![vec![string_cell("id")], vec![number_cell(-1.0)]]);
+    let result: Result<Vec<Id>> = from_grid_data(&data);
+
+    assert!(matches!(
+        result,
+        Err(Error::IntegerOutOfRange { ty: "u128", .. })
+    ));
+
+    let data = grid_data(vec![vec![string_cell("id")], vec![number_cell(1.5)]]);
+    let result: Result<Vec<Id>> = from_grid_data(&data);
+
+    assert!(matches!(result, Err(Error::NotInteger(_))));
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Grade {
+        grade: char,
+    }
+
+    let data = grid_data(vec![vec![string_cell("grade")], vec![string_cell("AB")]]);
+    let result: Result<Vec<Grade>> = from_grid_data(&data);
+
+    assert!(matches!(result, Err(Error::NotChar(_))));
+}
+
+#[test]
+fn test_large_integers() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Test {
+        id: u64,
+    }
+
+    let formatted_number_cell = |n: f64, formatted: &str| CellData {
+        formatted_value: Some(formatted.to_owned()),
+        ..number_cell(n)
+    };
+
+    // 2^60 is stored exactly and displayed in full.
+    let data = grid_data(vec![
+        vec![string_cell("id")],
+        vec![formatted_number_cell(
+            1152921504606846976.0,
+            "1152921504606846976",
+        )],
+    ]);
+
+    let result: Vec<Test> = from_grid_data(&data).unwrap();
+
+    assert_eq!(
+        vec![Test {
+            id: 1152921504606846976
+        }],
+        result
+    );
+
+    // 2^53 may be a rounded 2^53 + 1, so it needs its formatted text too.
+    let data = grid_data(vec![
+        vec![string_cell("id")],
+        vec![formatted_number_cell(
+            9007199254740992.0,
+            "9007199254740993",
+        )],
+        vec![formatted_number_cell(
+            9007199254740992.0,
+            "9007199254740992",
+        )],
+    ]);
+
+    let result: Result<Vec<Test>> = from_grid_data(&data);
+
+    assert!(matches!(result, Err(Error::PrecisionLoss(text)) if text == "9007199254740993"));
+
+    // 12345678901234567891 was rounded when it was typed in.
+    let data = grid_data(vec![
+        vec![string_cell("id")],
+        vec![formatted_number_cell(
+            12345678901234567891.0,
+            "12,345,678,901,234,600,000",
+        )],
+    ]);
+
+    let result: Result<Vec<Test>> = from_grid_data(&data);
+
+    assert!(matches!(result, Err(Error::PrecisionLoss(_))));
+
+    let data = grid_data(vec![
+        vec![string_cell("id")],
+        vec![string_cell("12345678901234567891")],
+        vec![formatted_number_cell(1234567.0, "1,234,567")],
+    ]);
+
+    let options = Options {
+        integers_from_text: true,
+        ..Options::default()
+    };
+
+    let result: Vec<Test> = from_grid_data_with_options(&data, &options).unwrap();
+
+    assert_eq!(
+        vec![
+            Test {
+                id: 12345678901234567891
+            },
+            Test { id: 1234567 }
+        ],
+        result
+    );
+
+    // Exactly representable values that don't fit are rejected, not clamped.
+    for (number, formatted) in [
+        (18446744073709551616.0, "18446744073709551616"),
+        (-5.0, "-5"),
+    ] {
+        let data = grid_data(vec![
+            vec![string_cell("id")],
+            vec![formatted_number_cell(number, formatted)],
+        ]);
+
+        let result: Result<Vec<Test>> = from_grid_data(&data);
+
+        assert!(
+            matches!(&result, Err(Error::IntegerOutOfRange { value, ty: "u64" }) if value == formatted),
+            "{:?}",
+            result
+        );
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Signed {
+        id: i64,
+    }
+
+    let data = grid_data(vec![
+        vec![string_cell("id")],
+        vec![formatted_number_cell(
+            9223372036854775808.0,
+            "9223372036854775808",
+        )],
+    ]);
+
+    let result: Result<Vec<Signed>> = from_grid_data(&data);
+
+    assert!(matches!(
+        result,
+        Err(Error::IntegerOutOfRange { ty: "i64", .. })
+    ));
+}
+
+#[test]
+fn test_row_map() {
+    use std::collections::BTreeMap;
+
+    let data = grid_data(vec![
+        vec![string_cell("col1"), string_cell("col2")],
+        vec![string_cell("v1"), string_cell("v2")],
+    ]);
+
+    let expected = vec![BTreeMap::from([
+        ("col1".to_owned(), "v1".to_owned()),
+        ("col2".to_owned(), "v2".to_owned()),
+    ])];
+
+    let result: Vec<BTreeMap<String, String>> = from_grid_data(&data).unwrap();
+
+    assert_eq!(expected, result)
+}
+
+#[test]
+fn test_spreadsheet_json() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Test {
+        name: String,
+        price: f64,
+    }
+
+    let json = r#"{
+        "spreadsheetId": "abc",
+        "sheets": [{
+            "properties": {"sheetId": 0, "title": "Prices"},
+            "data": [{
+                "rowData": [
+                    {"values": [
+                        {"formattedValue": "name", "effectiveValue": {"stringValue": "name"}},
+                        {"formattedValue": "price", "effectiveValue": {"stringValue": "price"}}
+                    ]},
+                    {"values": [
+                        {"formattedValue": "Apple", "effectiveValue": {"stringValue": "Apple"}},
+                        {"formattedValue": "0.5", "effectiveValue": {"numberValue": 0.5}}
+                    ]}
+                ]
+            }]
+        }]
+    }"#;
+
+    let expected = vec![Test {
+        name: "Apple".to_owned(),
+        price: 0.5,
+    }];
+
+    let result: Vec<Test> = from_spreadsheet_json(json).unwrap();
+    assert_eq!(expected, result);
+
+    let result: Vec<Test> = from_spreadsheet_reader(json.as_bytes()).unwrap();
+    assert_eq!(expected, result);
+
+    let result: Result<Vec<Test>> = from_spreadsheet_json(r#"{"sheets": []}"#);
+    assert!(matches!(result, Err(Error::MissingSheet)));
+}
+
+#[test]
+fn test_sheet_grid_data() {
+    use crate::source::fixture_spreadsheet;
+
+    let spreadsheet = fixture_spreadsheet(vec![
+        ("First", vec![vec!["name"], vec!["Apple"]]),
+        ("Second", vec![vec!["id"], vec!["1"]]),
+    ]);
+
+    let first = sheet_grid_data(spreadsheet.clone(), None).unwrap();
+    let names: Vec<HashMap<String, String>> = from_grid_data(&first).unwrap();
+    assert_eq!(names[0]["name"], "Apple");
+
+    let second = sheet_grid_data(spreadsheet.clone(), Some("Second")).unwrap();
+    let ids: Vec<HashMap<String, String>> = from_grid_data(&second).unwrap();
+    assert_eq!(ids[0]["id"], "1");
+
+    let result = sheet_grid_data(spreadsheet.clone(), Some("Third"));
+    assert!(matches!(result, Err(Error::MissingSheet)));
+
+    let mut spreadsheet = spreadsheet;
+    spreadsheet.sheets.as_mut().unwrap()[0].data = None;
+    let result = sheet_grid_data(spreadsheet, None);
+    assert!(matches!(result, Err(Error::NotGridSheet)));
+}
+
+#[cfg(test)]
+pub(crate) async fn serve_json(
+    body: String,
+) -> (
+    std::net::SocketAddr,
+    std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+) {
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Body, Request, Response, Server};
+    use std::sync::{Arc, Mutex};
+
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let seen = requests.clone();
+
+    let make_service = make_service_fn(move |_| {
+        let body = body.clone();
+        let seen = seen.clone();
+        async move {
+            Ok::<_, std::convert::Infallible>(service_fn(move |request: Request<Body>| {
+                let body = body.clone();
+                seen.lock().unwrap().push(request.uri().to_string());
+                async move {
+                    Ok::<_, std::convert::Infallible>(
+                        Response::builder()
+                            .header("content-type", "application/json")
+                            .body(Body::from(body))
+                            .unwrap(),
+                    )
+                }
+            }))
+        }
+    });
+
+    let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+    let addr = server.local_addr();
+    tokio::spawn(server);
+
+    (addr, requests)
+}
+
+#[cfg(test)]
+pub(crate) fn test_hub(
+    addr: std::net::SocketAddr,
+) -> google_sheets4::Sheets<google_sheets4::hyper::client::HttpConnector> {
+    use google_sheets4::hyper::client::HttpConnector;
+    use google_sheets4::hyper::Client;
+
+    let client = Client::builder().build(HttpConnector::new());
+    let mut sheets = google_sheets4::Sheets::new(client, google_sheets4::client::NoToken);
+    sheets.base_url(format!("http://{}/", addr));
+
+    sheets
+}
+
+#[tokio::test]
+async fn test_from_spreadsheet_custom_connector() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Test {
+        name: String,
+    }
+
+    let spreadsheet = crate::source::fixture_spreadsheet(vec![(
+        "Sheet1",
+        vec![vec!["name"], vec!["Apple"], vec!["Pear"]],
+    )]);
+    let (addr, requests) = serve_json(serde_json::to_string(&spreadsheet).unwrap()).await;
+
+    let result: Vec<Test> = from_spreadsheet(&test_hub(addr), "abc").await.unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert!(requests[0].contains("fields=sheets%28properties%2Cdata%28"));
+
+    assert_eq!(
+        vec![
+            Test {
+                name: "Apple".to_owned()
+            },
+            Test {
+                name: "Pear".to_owned()
+            }
+        ],
+        result
+    );
+}
+
+#[test]
+fn test_column_mapping() {
+    use crate::row::Column;
+    use google_sheets4::api::{CellFormat, NumberFormat};
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Item {
+        name: String,
+        price: f64,
+        sku: String,
+    }
+
+    let column = |field, header, letter, format| Column {
+        field,
+        header,
+        letter,
+        required: true,
+        format,
+    };
+    let options = Options {
+        columns: vec![
+            column("name", None, None, None),
+            column(
+                "price",
+                Some("Unit Price"),
+                None,
+                Some(ColumnFormat::Currency),
+            ),
+            column("sku", None, Some("D"), None),
+        ],
+        ..Options::default()
+    };
+    let currency = |n| CellData {
+        effective_format: Some(CellFormat {
+            number_format: Some(NumberFormat {
+                type_: Some("CURRENCY".to_owned()),
+                ..NumberFormat::default()
+            }),
+            ..CellFormat::default()
+        }),
+        ..number_cell(n)
+    };
+
+    let data = grid_data(vec![
+        vec![string_cell("name"), string_cell("Unit Price")],
+        vec![
+            string_cell("Apple"),
+            currency(1.5),
+            CellData::default(),
+            string_cell("A-1"),
+        ],
+    ]);
+    let items: Vec<Item> = from_grid_data_with_options(&data, &options).unwrap();
+    assert_eq!(
+        items,
+        vec![Item {
+            name: "Apple".to_owned(),
+            price: 1.5,
+            sku: "A-1".to_owned(),
+        }]
+    );
+
+    let data = grid_data(vec![
+        vec![string_cell("name"), string_cell("Unit Price")],
+        vec![string_cell("Apple"), number_cell(1.5)],
+    ]);
+    let err = from_grid_data_with_options::<Vec<Item>>(&data, &options).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::UnexpectedFormat {
+            row: 2,
+            expected: ColumnFormat::Currency,
+            ..
+        }
+    ));
+
+    let data = grid_data(vec![vec![string_cell("name"), string_cell("Price")]]);
+    let err = from_grid_data_with_options::<Vec<Item>>(&data, &options).unwrap_err();
+    assert!(matches!(err, Error::MissingColumn(name) if name == "Unit Price"));
+
+    // Letters name sheet columns, wherever the grid starts.
+    let mut data = grid_data(vec![
+        vec![string_cell("name"), string_cell("Unit Price")],
+        vec![
+            string_cell("Apple"),
+            currency(1.5),
+            string_cell("A-1"),
+            string_cell("WRONG"),
+        ],
+    ]);
+    data.start_column = Some(1);
+    let items: Vec<Item> = from_grid_data_with_options(&data, &options).unwrap();
+    assert_eq!(items[0].sku, "A-1");
+
+    let left_of_grid = Options {
+        columns: vec![column("sku", None, Some("A"), None)],
+        ..Options::default()
+    };
+    let err = from_grid_data_with_options::<Vec<Item>>(&data, &left_of_grid).unwrap_err();
+    assert!(matches!(err, Error::MissingColumn(name) if name == "A"));
+
+    // So does a declared header.
+    let data = grid_data(vec![
+        vec![
+            string_cell("name"),
+            string_cell("price"),
+            string_cell("Unit Price"),
+            string_cell("sku"),
+        ],
+        vec![
+            string_cell("Apple"),
+            number_cell(1.0),
+            currency(1.5),
+            string_cell("A-1"),
+        ],
+    ]);
+    let items: Vec<Item> = from_grid_data_with_options(&data, &options).unwrap();
+    assert_eq!(items[0].price, 1.5);
+}
+
+#[test]
+fn test_column_positions() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Item {
+        #[serde(rename = "$A")]
+        name: String,
+        price: f64,
+    }
+
+    let data = grid_data(vec![
+        vec![string_cell("Product (renamed)"), string_cell("Cost")],
+        vec![string_cell("Apple"), number_cell(1.5)],
+    ]);
+    let mut options = Options::default();
+    options
+        .positions
+        .insert("price".to_owned(), "#2".to_owned());
+
+    let items: Vec<Item> = from_grid_data_with_options(&data, &options).unwrap();
+    assert_eq!(
+        items,
+        vec![Item {
+            name: "Apple".to_owned(),
+            price: 1.5,
+        }]
+    );
+
+    options.positions.insert("price".to_owned(), "B".to_owned());
+    let err = from_grid_data_with_options::<Vec<Item>>(&data, &options).unwrap_err();
+    assert!(matches!(err, Error::InvalidPosition(position) if position == "B"));
+
+    // A position wins over a header that still carries the field's name,
+    // and names a sheet column wherever the grid starts.
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Product {
+        name: String,
+        price: f64,
+    }
+
+    let mut data = grid_data(vec![
+        vec![
+            string_cell("name"),
+            string_cell("price"),
+            string_cell("new price"),
+        ],
+        vec![string_cell("Apple"), number_cell(1.5), number_cell(2.0)],
+    ]);
+    data.start_column = Some(1);
+    options
+        .positions
+        .insert("price".to_owned(), "$D".to_owned());
+    let products: Vec<Product> = from_grid_data_with_options(&data, &options).unwrap();
+    assert_eq!(
+        products,
+        vec![Product {
+            name: "Apple".to_owned(),
+            price: 2.0,
+        }]
+    );
+}
+
+#[test]
+fn test_skip_rows() {
+    use google_sheets4::api::{DimensionProperties, RowData};
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Row {
+        name: String,
+    }
+
+    let mut data = grid_data(vec![
+        vec![string_cell("name")],
+        vec![string_cell("# not a row")],
+        vec![string_cell("a")],
+        vec![string_cell("hidden")],
+        vec![string_cell("filtered")],
+        vec![string_cell("b")],
+    ]);
+    data.row_data
+        .as_mut()
+        .unwrap()
+        .insert(3, RowData { values: None });
+    let hidden = |by_user, by_filter| DimensionProperties {
+        hidden_by_user: Some(by_user),
+        hidden_by_filter: Some(by_filter),
+        ..DimensionProperties::default()
+    };
+    data.row_metadata = Some(vec![
+        hidden(false, false),
+        hidden(false, false),
+        hidden(false, false),
+        hidden(false, false),
+        hidden(true, false),
+        hidden(false, true),
+    ]);
+
+    let options = Options {
+        skip_hidden_rows: true,
+        skip_filtered_rows: true,
+        skip_comment_rows: true,
+        skip_blank_rows: true,
+        ..Options::default()
+    };
+    let rows: Vec<Row> = from_grid_data_with_options(&data, &options).unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            Row {
+                name: "a".to_owned()
+            },
+            Row {
+                name: "b".to_owned()
+            },
+        ]
+    );
+
+    let rows: Vec<Option<Row>> = from_grid_data(&data).unwrap();
+    assert_eq!(rows.len(), 6);
+}
+
+#[test]
+fn test_dropdowns() {
+    use google_sheets4::api::{BooleanCondition, ConditionValue, DataValidationRule};
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    enum Status {
+        Open,
+        Closed,
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Ticket {
+        status: Status,
+    }
+
+    let dropdown = |value: &str, choices: &[&str]| CellData {
+        data_validation: Some(DataValidationRule {
+            condition: Some(BooleanCondition {
+                type_: Some("ONE_OF_LIST".to_owned()),
+                values: Some(
+                    choices
+                        .iter()
+                        .map(|choice| ConditionValue {
+                            user_entered_value: Some(choice.to_string()),
+                            ..ConditionValue::default()
+                        })
+                        .collect(),
+                ),
+            }),
+            ..DataValidationRule::default()
+        }),
+        ..string_cell(value)
+    };
+    let options = Options {
+        validate_dropdowns: true,
+        ..Options::default()
+    };
+
+    let data = grid_data(vec![
+        vec![string_cell("status")],
+        vec![dropdown("Open", &["Open", "Closed"])],
+    ]);
+    let tickets: Vec<Ticket> = from_grid_data_with_options(&data, &options).unwrap();
+    assert_eq!(
+        tickets,
+        vec![Ticket {
+            status: Status::Open
+        }]
+    );
+
+    let data = grid_data(vec![
+        vec![string_cell("status")],
+        vec![dropdown("Open", &["Open", "Pending"])],
+    ]);
+    let err = from_grid_data_with_options::<Vec<Ticket>>(&data, &options).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "dropdown of column \"status\" lacks variants [\"Closed\"] and offers unknown values [\"Pending\"]"
+    );
+    assert!(from_grid_data::<Vec<Ticket>>(&data).is_ok());
+
+    // Multi-select dropdowns are read into a list, checked all the same,
+    // even when the first row's cell is empty.
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Labels {
+        labels: Vec<Status>,
+    }
+
+    let data = grid_data(vec![
+        vec![string_cell("labels")],
+        vec![dropdown("", &["Open", "Pending"])],
+        vec![dropdown("Open, Pending", &["Open", "Pending"])],
+    ]);
+    let err = from_grid_data_with_options::<Vec<Labels>>(&data, &options).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::DropdownMismatch { column, missing_variants, unknown_values }
+            if column == "labels" && missing_variants == ["Closed"] && unknown_values == ["Pending"]
+    ));
+}
@@ -2,28 +2,115 @@ use std::fmt::{self, Display};
 
 use serde::de;
 
+use crate::options::BytesEncoding;
+use crate::row::ColumnFormat;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug)]
 pub enum Error {
-    GoogleSheetsError(google_sheets4::Error),
+    GoogleSheetsError(Box<google_sheets4::Error>),
+
+    JsonError(serde_json::Error),
+
+    IoError(std::io::Error),
+
+    UnknownSpreadsheet(String),
+
+    InvalidRange(String),
+
+    UnknownNamedRange(String),
+
+    // No developer metadata with the given key locates a range.
+    UnknownMetadataKey(String),
+
+    // A column position that is neither `$` and letters nor `#` and a
+    // column number.
+    InvalidPosition(String),
 
     MissingSheet,
 
+    // No table matched, see `find_tables`. Holds the table's index or a
+    // description of its headers.
+    MissingTable(String),
+
     NotGridSheet,
 
     // There must be at least one row which acts as the heading row when the
     // deserializer needs to match field names to columns.
     ZeroRows,
 
-    HeaderMustBeString,
+    // A header cell holding a number, bool or error instead of text, rejected
+    // by `NonTextHeaders::Error`. Holds the column letters.
+    HeaderMustBeString(String),
+
+    // Header cells of the given columns have the same text.
+    DuplicateHeader {
+        name: String,
+        columns: Vec<String>,
+    },
+
+    // A blank header cell, rejected by `BlankHeaders::Error`.
+    BlankHeader(String),
+
+    MissingColumn(String),
+
+    // The header row doesn't match the fields of the row struct: fields
+    // without a column, (header, field) pairs that look like misspellings,
+    // and columns no field reads.
+    HeaderMismatch {
+        missing: Vec<String>,
+        misspelled: Vec<(String, String)>,
+        unexpected: Vec<String>,
+    },
 
     MissingValue(String),
 
+    // The dropdown of a column read into an enum doesn't offer exactly the
+    // enum's variants.
+    DropdownMismatch {
+        column: String,
+        missing_variants: Vec<String>,
+        unknown_values: Vec<String>,
+    },
+
+    // A non-blank cell whose number format isn't the one its column is
+    // declared with. Rows are 1-based sheet row numbers.
+    UnexpectedFormat {
+        column: String,
+        row: u32,
+        expected: ColumnFormat,
+        found: Option<String>,
+    },
+
+    // Two rows have the same value in the key column when deserializing a
+    // sheet into a map. Rows are 1-based sheet row numbers.
+    DuplicateKey {
+        key: String,
+        first_row: u32,
+        second_row: u32,
+    },
+
     NotNumber(Option<String>),
 
+    NotInteger(String),
+
+    // An integer cell whose value doesn't fit the target type.
+    IntegerOutOfRange {
+        value: String,
+        ty: &'static str,
+    },
+
+    // A number cell holds an integer too large for f64 to represent exactly,
+    // so its value may no longer be what was entered.
+    PrecisionLoss(String),
+
+    NotChar(String),
+
     NotBool,
 
+    NotBytes(BytesEncoding, String),
+
     // One or more variants that can be created by data structures through the
     // `ser::Error` and `de::Error` traits. For example the Serialize impl for
     // Mutex<T> might return an error because the mutex is poisoned, or the
@@ -39,7 +126,19 @@ pub enum Error {
 
 impl From<google_sheets4::Error> for Error {
     fn from(value: google_sheets4::Error) -> Self {
-        Error::GoogleSheetsError(value)
+        Error::GoogleSheetsError(Box::new(value))
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Error::JsonError(value)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Error::IoError(value)
     }
 }
 
@@ -53,20 +152,133 @@ impl Display for Error {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Error::ZeroRows => formatter.write_str("zero rows in sheet"),
-            Error::HeaderMustBeString => formatter.write_str("header cell must be of string type"),
+            Error::HeaderMustBeString(column) => formatter.write_fmt(format_args!(
+                "header cell in column {} must be of string type",
+                column
+            )),
+            Error::DuplicateHeader { name, columns } => formatter.write_fmt(format_args!(
+                "header {:?} appears in columns {}",
+                name,
+                columns.join(", ")
+            )),
+            Error::BlankHeader(column) => {
+                formatter.write_fmt(format_args!("header cell in column {} is blank", column))
+            }
             Error::Message(msg) => formatter.write_str(msg),
             Error::Eof => formatter.write_str("unexpected end of input"),
+            Error::MissingColumn(name) => {
+                formatter.write_fmt(format_args!("column {:?} not found in header row", name))
+            }
+            Error::HeaderMismatch {
+                missing,
+                misspelled,
+                unexpected,
+            } => {
+                let mut problems = Vec::new();
+                if !missing.is_empty() {
+                    problems.push(format!("missing columns {:?}", missing));
+                }
+                for (header, field) in misspelled {
+                    problems.push(format!(
+                        "column {:?} should probably be {:?}",
+                        header, field
+                    ));
+                }
+                if !unexpected.is_empty() {
+                    problems.push(format!("unexpected columns {:?}", unexpected));
+                }
+                formatter.write_fmt(format_args!(
+                    "header row doesn't match fields: {}",
+                    problems.join("; ")
+                ))
+            }
+            Error::DropdownMismatch {
+                column,
+                missing_variants,
+                unknown_values,
+            } => {
+                let mut problems = Vec::new();
+                if !missing_variants.is_empty() {
+                    problems.push(format!("lacks variants {:?}", missing_variants));
+                }
+                if !unknown_values.is_empty() {
+                    problems.push(format!("offers unknown values {:?}", unknown_values));
+                }
+                formatter.write_fmt(format_args!(
+                    "dropdown of column {:?} {}",
+                    column,
+                    problems.join(" and ")
+                ))
+            }
             Error::MissingValue(s) => formatter.write_fmt(format_args!(
                 "expected value but it wasn't present, ctx: {}",
                 s
             )),
+            Error::UnexpectedFormat {
+                column,
+                row,
+                expected,
+                found,
+            } => formatter.write_fmt(format_args!(
+                "column {:?} row {} should be formatted as {}, found {}",
+                column,
+                row,
+                expected.as_str(),
+                found.as_deref().unwrap_or("no format")
+            )),
+            Error::DuplicateKey {
+                key,
+                first_row,
+                second_row,
+            } => formatter.write_fmt(format_args!(
+                "duplicate key {:?} in rows {} and {}",
+                key, first_row, second_row
+            )),
             Error::NotNumber(s) => {
                 formatter.write_fmt(format_args!("expected number value, found {:?}", s))
             }
+            Error::NotInteger(s) => {
+                formatter.write_fmt(format_args!("expected integer value, found {:?}", s))
+            }
+            Error::IntegerOutOfRange { value, ty } => {
+                formatter.write_fmt(format_args!("{} does not fit in {}", value, ty))
+            }
+            Error::PrecisionLoss(s) => formatter.write_fmt(format_args!(
+                "integer {} cannot be represented exactly by a sheets number",
+                s
+            )),
+            Error::NotChar(s) => {
+                formatter.write_fmt(format_args!("expected single character, found {:?}", s))
+            }
             Error::NotBool => formatter.write_str("expected bool value"),
+            Error::NotBytes(encoding, s) => {
+                formatter.write_fmt(format_args!("expected {} bytes, found {:?}", encoding, s))
+            }
             Error::GoogleSheetsError(err) => {
                 formatter.write_fmt(format_args!("google_sheets error: {}", err))
             }
+            Error::JsonError(err) => formatter.write_fmt(format_args!("json error: {}", err)),
+            Error::IoError(err) => formatter.write_fmt(format_args!("io error: {}", err)),
+            Error::UnknownSpreadsheet(id) => {
+                formatter.write_fmt(format_args!("spreadsheet {:?} not found", id))
+            }
+            Error::InvalidRange(range) => {
+                formatter.write_fmt(format_args!("invalid A1 range {:?}", range))
+            }
+            Error::UnknownNamedRange(name) => {
+                formatter.write_fmt(format_args!("named range {:?} not found", name))
+            }
+            Error::UnknownMetadataKey(key) => formatter.write_fmt(format_args!(
+                "no range tagged with developer metadata key {:?}",
+                key
+            )),
+            Error::InvalidPosition(position) => formatter.write_fmt(format_args!(
+                "invalid column position {:?}, expected e.g. \"$C\" or \"#3\"",
+                position
+            )),
+            Error::MissingTable(table) => {
+                formatter.write_fmt(format_args!("table {} not found in sheet", table))
+            }
             Error::MissingSheet => formatter.write_str("sheet 0 not found in spreadsheet"),
             Error::NotGridSheet => formatter.write_str("spreadsheet is not a grid sheet"),
             /* and so forth */
@@ -0,0 +1,63 @@
+use crate::options::Options;
+
+/// The `fields` mask sent when fetching grid data. By default only what the
+/// deserializer reads is requested, leaving out formatting, borders, text
+/// runs and the like; options that rely on further metadata widen it.
+pub fn field_mask(options: &Options) -> String {
+    let mut sheet_fields = vec!["properties"];
+    let mut data_fields = vec!["startRow", "startColumn"];
+    let mut cell_fields = vec![
+        "effectiveValue",
+        "formattedValue",
+        "effectiveFormat/numberFormat",
+    ];
+
+    if options.fill_merged_cells {
+        sheet_fields.push("merges");
+    }
+
+    if options.skip_hidden_rows || options.skip_filtered_rows {
+        data_fields.push("rowMetadata(hiddenByUser,hiddenByFilter)");
+    }
+
+    if options.validate_dropdowns {
+        cell_fields.push("dataValidation");
+    }
+
+    if options.skip_hidden_columns {
+        data_fields.push("columnMetadata(hiddenByUser)");
+    }
+
+    format!(
+        "sheets({},data({},rowData(values({}))))",
+        sheet_fields.join(","),
+        data_fields.join(","),
+        cell_fields.join(",")
+    )
+}
+
+#[test]
+fn test_field_mask() {
+    assert_eq!(
+        "sheets(properties,data(startRow,startColumn,rowData(values(effectiveValue,formattedValue,effectiveFormat/numberFormat))))",
+        field_mask(&Options::default())
+    );
+
+    let options = Options {
+        fill_merged_cells: true,
+        ..Options::default()
+    };
+
+    assert!(field_mask(&options).starts_with("sheets(properties,merges,data("));
+
+    let options = Options {
+        skip_filtered_rows: true,
+        skip_hidden_columns: true,
+        validate_dropdowns: true,
+        ..Options::default()
+    };
+
+    assert!(field_mask(&options)
+        .contains("data(startRow,startColumn,rowMetadata(hiddenByUser,hiddenByFilter),columnMetadata(hiddenByUser),rowData("));
+    assert!(field_mask(&options).contains("effectiveFormat/numberFormat,dataValidation)"));
+}
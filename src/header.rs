@@ -0,0 +1,326 @@
+use google_sheets4::api::{CellData, GridData};
+
+use crate::error::{Error, Result};
+use crate::options::{BlankHeaders, NonTextHeaders, Options};
+use crate::range::column_letters;
+
+// Builds the header map from the first row: the text of each header cell by
+// column index, or `None` for blank cells. Header cells must be unique, so
+// that every column is matched to at most one field, and are read by their
+// formatted text unless non-text headers are rejected. Hidden
+// columns are treated as blank when they are to be skipped.
+pub(crate) fn read_header<'de>(
+    cells: &'de [CellData],
+    grid_data: &GridData,
+    options: &Options,
+) -> Result<smallmap::Map<usize, Option<&'de str>>> {
+    let start_column: u32 = grid_data.start_column.unwrap_or(0).try_into().unwrap_or(0);
+    let letters = |idx: usize| column_letters(start_column + idx as u32);
+    let hidden = |idx: usize| {
+        options.skip_hidden_columns
+            && grid_data
+                .column_metadata
+                .as_deref()
+                .and_then(|columns| columns.get(idx))
+                .is_some_and(|column| column.hidden_by_user == Some(true))
+    };
+    let mut types = smallmap::Map::new();
+
+    for (idx, cell) in cells.iter().enumerate() {
+        if hidden(idx) {
+            types.insert(idx, None);
+            continue;
+        }
+
+        let value = cell.effective_value.as_ref();
+        if options.non_text_headers == NonTextHeaders::Error
+            && value.is_some_and(|value| value.string_value.is_none())
+        {
+            return Err(Error::HeaderMustBeString(letters(idx)));
+        }
+
+        let header = cell
+            .formatted_value
+            .as_deref()
+            .filter(|h| !h.trim().is_empty());
+        if header.is_none() && options.blank_headers == BlankHeaders::Error {
+            return Err(Error::BlankHeader(letters(idx)));
+        }
+
+        types.insert(idx, header);
+    }
+
+    for idx in 0..cells.len() {
+        let name = match types.get(&idx) {
+            Some(Some(name)) => *name,
+            _ => continue,
+        };
+
+        let columns: Vec<_> = (idx..cells.len())
+            .filter(|other| types.get(other) == Some(&Some(name)))
+            .collect();
+        if columns.len() > 1 {
+            return Err(Error::DuplicateHeader {
+                name: name.to_owned(),
+                columns: columns.into_iter().map(letters).collect(),
+            });
+        }
+    }
+
+    Ok(types)
+}
+
+// Compares the header row with the fields of the row struct, so that every
+// missing and unexpected column is reported at once instead of as a serde
+// "missing field" error on the first row. Missing fields that are close to an
+// unexpected header are reported as a likely misspelling of it. Unexpected
+// headers that are left over are only reported with `report_unexpected`.
+pub(crate) fn check_fields(
+    headers: &[&str],
+    fields: &[&str],
+    report_unexpected: bool,
+) -> Result<()> {
+    let mut unexpected: Vec<&str> = headers
+        .iter()
+        .copied()
+        .filter(|header| !fields.contains(header))
+        .collect();
+    let mut missing = Vec::new();
+    let mut misspelled = Vec::new();
+
+    for &field in fields.iter().filter(|field| !headers.contains(field)) {
+        let closest = unexpected
+            .iter()
+            .enumerate()
+            .map(|(i, header)| (i, distance(&normalize(field), &normalize(header))))
+            .filter(|&(_, d)| d <= field.chars().count().div_ceil(3))
+            .min_by_key(|&(_, d)| d);
+
+        match closest {
+            Some((i, _)) => misspelled.push((unexpected.remove(i).to_owned(), field.to_owned())),
+            None => missing.push(field.to_owned()),
+        }
+    }
+
+    if !report_unexpected {
+        unexpected.clear();
+    }
+
+    if missing.is_empty() && misspelled.is_empty() && unexpected.is_empty() {
+        return Ok(());
+    }
+
+    Err(Error::HeaderMismatch {
+        missing,
+        misspelled,
+        unexpected: unexpected.into_iter().map(str::to_owned).collect(),
+    })
+}
+
+// Headers are compared ignoring case and word separators, so "Unit Price"
+// is a close match for `unit_price`.
+fn normalize(name: &str) -> Vec<char> {
+    name.chars()
+        .filter(|c| !c.is_whitespace() && *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+// Levenshtein distance.
+fn distance(a: &[char], b: &[char]) -> usize {
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.iter().enumerate() {
+        let mut cur = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = prev[j] + usize::from(ca != cb);
+            cur.push(substitute.min(prev[j + 1] + 1).min(cur[j] + 1));
+        }
+        prev = cur;
+    }
+
+    prev[b.len()]
+}
+
+#[test]
+fn test_check_fields() {
+    assert!(check_fields(&["name", "price"], &["price", "name"], true).is_ok());
+    assert!(check_fields(&["name", "notes"], &["name"], false).is_ok());
+
+    let err = check_fields(
+        &["name", "Unit Price", "Pirce", "notes"],
+        &["name", "unit_price", "price", "sku"],
+        true,
+    )
+    .unwrap_err();
+    match err {
+        Error::HeaderMismatch {
+            missing,
+            misspelled,
+            unexpected,
+        } => {
+            assert_eq!(missing, vec!["sku"]);
+            assert_eq!(
+                misspelled,
+                vec![
+                    ("Unit Price".to_owned(), "unit_price".to_owned()),
+                    ("Pirce".to_owned(), "price".to_owned()),
+                ]
+            );
+            assert_eq!(unexpected, vec!["notes"]);
+        }
+        err => panic!("unexpected error {:?}", err),
+    }
+}
+
+#[test]
+fn test_validate_headers() {
+    use crate::de::{from_grid_data_with_options, grid_data, string_cell};
+    use crate::options::Options;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Row {
+        name: String,
+        email: Option<String>,
+    }
+
+    let data = grid_data(vec![
+        vec![
+            string_cell("name"),
+            string_cell("e-mail"),
+            string_cell("age"),
+        ],
+        vec![string_cell("Ann")],
+    ]);
+    let options = Options {
+        validate_headers: true,
+        ..Options::default()
+    };
+
+    assert!(from_grid_data_with_options::<Vec<Row>>(&data, &Options::default()).is_ok());
+    let err = from_grid_data_with_options::<Vec<Row>>(&data, &options).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "header row doesn't match fields: column \"e-mail\" should probably be \"email\"; \
+         unexpected columns [\"age\"]"
+    );
+
+    // Without data rows, only declared columns can be checked.
+    let header_only = grid_data(vec![vec![string_cell("name"), string_cell("age")]]);
+    let rows: Vec<Row> = from_grid_data_with_options(&header_only, &options).unwrap();
+    assert!(rows.is_empty());
+
+    let declared = Options {
+        columns: ["name", "email"]
+            .into_iter()
+            .map(|field| crate::row::Column {
+                field,
+                header: None,
+                letter: None,
+                required: false,
+                format: None,
+            })
+            .collect(),
+        ..options.clone()
+    };
+    let err = from_grid_data_with_options::<Vec<Row>>(&header_only, &declared).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::HeaderMismatch { missing, unexpected, .. }
+            if missing == ["email"] && unexpected == ["age"]
+    ));
+}
+
+#[test]
+fn test_validate_grouped_headers() {
+    use crate::de::{from_grid_data_with_options, grid_data, string_cell};
+    use crate::options::{Grouping, Options};
+    use serde::Deserialize;
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Item {
+        product: String,
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Order {
+        order: String,
+        items: Vec<Item>,
+    }
+
+    let data = grid_data(vec![
+        vec![string_cell("order"), string_cell("product")],
+        vec![string_cell("A1"), string_cell("Apple")],
+    ]);
+    let options = Options {
+        validate_headers: true,
+        grouping: Some(Grouping {
+            key_columns: vec!["order".to_owned()],
+            children: "items".to_owned(),
+        }),
+        ..Options::default()
+    };
+
+    let orders: Vec<Order> = from_grid_data_with_options(&data, &options).unwrap();
+    assert_eq!(orders[0].items.len(), 1);
+}
+
+#[test]
+fn test_read_header() {
+    use crate::de::{number_cell, string_cell};
+    use google_sheets4::api::DimensionProperties;
+
+    let options = Options::default();
+    let cells = vec![string_cell("id"), CellData::default(), string_cell("name")];
+    let types = read_header(&cells, &GridData::default(), &options).unwrap();
+    assert_eq!(types.get(&1), Some(&None));
+    assert_eq!(types.get(&2), Some(&Some("name")));
+
+    let strict = Options {
+        blank_headers: BlankHeaders::Error,
+        ..Options::default()
+    };
+    let err = read_header(&cells, &GridData::default(), &strict).unwrap_err();
+    assert!(matches!(err, Error::BlankHeader(column) if column == "B"));
+
+    let cells = vec![string_cell("id"), string_cell("name"), string_cell("id")];
+    let grid_data = GridData {
+        start_column: Some(1),
+        ..GridData::default()
+    };
+    let err = read_header(&cells, &grid_data, &options).unwrap_err();
+    assert_eq!(err.to_string(), "header \"id\" appears in columns B, D");
+
+    let grid_data = GridData {
+        column_metadata: Some(vec![
+            DimensionProperties::default(),
+            DimensionProperties::default(),
+            DimensionProperties {
+                hidden_by_user: Some(true),
+                ..DimensionProperties::default()
+            },
+        ]),
+        ..GridData::default()
+    };
+    let hidden = Options {
+        skip_hidden_columns: true,
+        ..Options::default()
+    };
+    let types = read_header(&cells, &grid_data, &hidden).unwrap();
+    assert_eq!(types.get(&2), Some(&None));
+
+    let cells = vec![string_cell("id"), number_cell(2024.0)];
+    let types = read_header(&cells, &GridData::default(), &options).unwrap();
+    assert_eq!(types.get(&1), Some(&Some("2024")));
+
+    let strict = Options {
+        non_text_headers: NonTextHeaders::Error,
+        ..Options::default()
+    };
+    let err = read_header(&cells, &GridData::default(), &strict).unwrap_err();
+    assert!(matches!(err, Error::HeaderMustBeString(column) if column == "B"));
+}
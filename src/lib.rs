@@ -1,5 +1,45 @@
+mod cache;
+mod chunk;
+mod connector;
 mod de;
 mod error;
+mod fields;
+mod header;
+mod merge;
+mod metadata;
+mod named;
+mod options;
+mod range;
+mod row;
+pub mod schema;
+mod source;
+mod table;
+mod text;
 
-pub use de::{from_grid_data, from_spreadsheet, Deserializer};
+pub use cache::CachedSource;
+pub use chunk::{from_source_chunked, Chunking};
+pub use connector::Connector;
+pub use de::{
+    from_fetched_spreadsheet, from_grid_data, from_grid_data_with_options, from_spreadsheet,
+    from_spreadsheet_json, from_spreadsheet_reader, from_spreadsheet_with_options, sheet_grid_data,
+    Deserializer, MAX_SAFE_INTEGER,
+};
 pub use error::{Error, Result};
+pub use fields::field_mask;
+pub use merge::fill_merged_cells;
+pub use metadata::{
+    from_developer_metadata, from_developer_metadata_with_options, from_fetched_developer_metadata,
+    from_source_developer_metadata,
+};
+pub use named::{
+    from_fetched_named_range, from_named_range, from_named_range_with_options,
+    from_source_named_range,
+};
+pub use options::{BlankHeaders, BytesEncoding, EmptyItems, Grouping, NonTextHeaders, Options};
+pub use range::{column_index, column_letters, column_position, slice_grid_data, A1Range};
+pub use row::{Column, ColumnFormat, SheetRow};
+pub use source::{from_source, from_source_range, Fixture, JsonDir, SheetSource};
+pub use table::{find_tables, from_table, from_table_index, from_table_with_headers, Table};
+
+#[cfg(feature = "derive")]
+pub use serde_google_sheets_derive::SheetRow;
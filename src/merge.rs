@@ -0,0 +1,61 @@
+use google_sheets4::api::{CellData, GridData, GridRange, RowData};
+
+/// Copies the top-left value of each merged range into every other cell of
+/// that range. `merges` are sheet coordinates as found in `Sheet.merges`;
+/// parts of a range outside of `grid_data` are ignored.
+pub fn fill_merged_cells(grid_data: &mut GridData, merges: &[GridRange]) {
+    let start_row = grid_data.start_row.unwrap_or(0);
+    let start_column = grid_data.start_column.unwrap_or(0);
+
+    let rows = match grid_data.row_data.as_mut() {
+        Some(rows) => rows,
+        None => return,
+    };
+
+    for merge in merges {
+        let row_range = to_local(merge.start_row_index, merge.end_row_index, start_row);
+        let column_range = to_local(
+            merge.start_column_index,
+            merge.end_column_index,
+            start_column,
+        );
+
+        let (Some((top, bottom)), Some((left, right))) = (row_range, column_range) else {
+            continue;
+        };
+
+        let Some(top_left) = rows
+            .get(top)
+            .and_then(|row| row.values.as_ref())
+            .and_then(|values| values.get(left))
+            .cloned()
+        else {
+            continue;
+        };
+
+        for row in rows.iter_mut().take(bottom).skip(top) {
+            fill_row(row, left, right, &top_left);
+        }
+    }
+}
+
+// Converts a sheet-level [start, end) range into grid-local indices,
+// dropping ranges that start before the grid.
+fn to_local(start: Option<i32>, end: Option<i32>, offset: i32) -> Option<(usize, usize)> {
+    let start = usize::try_from(start.unwrap_or(0) - offset).ok()?;
+    let end = usize::try_from(end? - offset).ok()?;
+
+    Some((start, end))
+}
+
+fn fill_row(row: &mut RowData, left: usize, right: usize, value: &CellData) {
+    let values = row.values.get_or_insert_with(Vec::new);
+
+    if values.len() < right {
+        values.resize(right, CellData::default());
+    }
+
+    for cell in &mut values[left..right] {
+        *cell = value.clone();
+    }
+}
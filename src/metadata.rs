@@ -0,0 +1,369 @@
+use futures::future::try_join_all;
+use google_sheets4::api::{DeveloperMetadata, Spreadsheet};
+use google_sheets4::Sheets;
+use serde::de::DeserializeOwned;
+use tracing::instrument;
+
+use crate::connector::Connector;
+use crate::de::from_fetched_spreadsheet;
+use crate::error::{Error, Result};
+use crate::options::Options;
+use crate::range::A1Range;
+use crate::source::{from_source_range, select_range, sheet_title, SheetSource};
+
+// Only the developer metadata attached to rows and columns, which the API
+// reports as part of the grid data.
+const DIMENSION_METADATA_FIELDS: &str =
+    "sheets(data(rowMetadata/developerMetadata,columnMetadata/developerMetadata))";
+
+// Every piece of developer metadata in the spreadsheet: on the spreadsheet
+// itself, on its sheets and on the rows and columns of the grid data.
+fn all_metadata(spreadsheet: &Spreadsheet) -> impl Iterator<Item = &DeveloperMetadata> {
+    let sheets = spreadsheet.sheets.iter().flatten().flat_map(|sheet| {
+        let dimensions = sheet.data.iter().flatten().flat_map(|grid_data| {
+            grid_data
+                .row_metadata
+                .iter()
+                .flatten()
+                .chain(grid_data.column_metadata.iter().flatten())
+                .flat_map(|dimension| dimension.developer_metadata.iter().flatten())
+        });
+
+        sheet.developer_metadata.iter().flatten().chain(dimensions)
+    });
+
+    spreadsheet
+        .developer_metadata
+        .iter()
+        .flatten()
+        .chain(sheets)
+}
+
+// Works out the range tagged with the metadata key `key`:
+//
+// - metadata on rows and/or columns bounds the range to those rows and
+//   columns of their sheet, so it moves along as rows are inserted above it
+// - metadata on a sheet selects the whole sheet
+// - metadata on the spreadsheet holds the range in A1 notation as its value
+//
+// in that order of preference.
+//
+// Sheet titles are looked up in `spreadsheet`, which needn't hold the
+// metadata itself.
+fn locate<'a>(
+    spreadsheet: &Spreadsheet,
+    metadata: impl Iterator<Item = &'a DeveloperMetadata>,
+    key: &str,
+) -> Result<A1Range> {
+    let title = |sheet_id: Option<i32>| {
+        spreadsheet
+            .sheets
+            .iter()
+            .flatten()
+            .find(|sheet| {
+                let id = sheet.properties.as_ref().and_then(|p| p.sheet_id);
+                id.unwrap_or(0) == sheet_id.unwrap_or(0)
+            })
+            .and_then(sheet_title)
+            .ok_or(Error::MissingSheet)
+    };
+
+    let tagged: Vec<_> = metadata
+        .filter(|metadata| metadata.metadata_key.as_deref() == Some(key))
+        .filter_map(|metadata| Some((metadata, metadata.location.as_ref()?)))
+        .collect();
+
+    let dimensions: Vec<_> = tagged
+        .iter()
+        .filter_map(|(_, location)| location.dimension_range.as_ref())
+        .collect();
+
+    if let Some(first) = dimensions.first() {
+        let mut range = A1Range::sheet(title(first.sheet_id)?);
+        let index = |v: Option<i32>| v.map(|v| v.max(0) as u32);
+
+        for dimension in dimensions
+            .iter()
+            .filter(|dimension| dimension.sheet_id.unwrap_or(0) == first.sheet_id.unwrap_or(0))
+        {
+            match dimension.dimension.as_deref() {
+                Some("ROWS") => {
+                    range.start_row = index(dimension.start_index);
+                    range.end_row = index(dimension.end_index);
+                }
+                Some("COLUMNS") => {
+                    range.start_column = index(dimension.start_index);
+                    range.end_column = index(dimension.end_index);
+                }
+                _ => {}
+            }
+        }
+
+        return Ok(range);
+    }
+
+    if let Some((_, location)) = tagged
+        .iter()
+        .find(|(_, location)| location.sheet_id.is_some())
+    {
+        return Ok(A1Range::sheet(title(location.sheet_id)?));
+    }
+
+    if let Some((metadata, _)) = tagged
+        .iter()
+        .find(|(_, location)| location.spreadsheet == Some(true))
+    {
+        return metadata
+            .metadata_value
+            .as_deref()
+            .unwrap_or_default()
+            .parse();
+    }
+
+    Err(Error::UnknownMetadataKey(key.to_owned()))
+}
+
+/// Deserializes the range tagged with the developer metadata key `key` of an
+/// already fetched spreadsheet, which must include grid data.
+///
+/// Metadata on rows and columns bounds the range to them, metadata on a sheet
+/// selects that sheet, and metadata on the spreadsheet itself holds the range
+/// in A1 notation as its value.
+#[instrument(skip(spreadsheet, options))]
+pub fn from_fetched_developer_metadata<T>(
+    spreadsheet: &Spreadsheet,
+    key: &str,
+    options: &Options,
+) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let range = locate(spreadsheet, all_metadata(spreadsheet), key)?.to_string();
+
+    from_fetched_spreadsheet(select_range(spreadsheet, &range)?, options)
+}
+
+/// Deserializes the range tagged with the developer metadata key `key` of a
+/// spreadsheet read from `source`, see [`from_fetched_developer_metadata`].
+///
+/// Row and column metadata is only reported along with grid data, so the
+/// metadata of every sheet is fetched (without cell values, all sheets at
+/// once) before the range itself. With
+/// [`Options::search_developer_metadata`], sources that can search developer
+/// metadata, such as the Sheets API, find the key in one request instead.
+#[instrument(skip(source, options))]
+pub async fn from_source_developer_metadata<T, S>(
+    source: &S,
+    spreadsheet_id: &str,
+    key: &str,
+    options: &Options,
+) -> Result<T>
+where
+    T: DeserializeOwned,
+    S: SheetSource,
+{
+    let mut spreadsheet = source.spreadsheet(spreadsheet_id).await?;
+
+    let found = match options.search_developer_metadata {
+        true => {
+            source
+                .search_developer_metadata(spreadsheet_id, key)
+                .await?
+        }
+        false => None,
+    };
+
+    let range = match found {
+        Some(found) => locate(&spreadsheet, found.iter(), key)?,
+        None => {
+            let sheets = spreadsheet.sheets.iter_mut().flatten();
+            let (ranges, sheets): (Vec<_>, Vec<_>) = sheets
+                .filter_map(|sheet| Some((A1Range::sheet(sheet_title(sheet)?).to_string(), sheet)))
+                .unzip();
+
+            let fetched =
+                try_join_all(ranges.iter().map(|range| {
+                    source.grid_data(spreadsheet_id, range, DIMENSION_METADATA_FIELDS)
+                }))
+                .await?;
+
+            for (sheet, fetched) in sheets.into_iter().zip(fetched) {
+                sheet.data = fetched
+                    .sheets
+                    .into_iter()
+                    .flatten()
+                    .next()
+                    .and_then(|sheet| sheet.data);
+            }
+
+            locate(&spreadsheet, all_metadata(&spreadsheet), key)?
+        }
+    };
+
+    from_source_range(source, spreadsheet_id, &range.to_string(), options).await
+}
+
+/// Fetches and deserializes the range tagged with the developer metadata key
+/// `key`, see [`from_source_developer_metadata`].
+pub async fn from_developer_metadata<T, C>(
+    sheets: &Sheets<C>,
+    spreadsheet_id: &str,
+    key: &str,
+) -> Result<T>
+where
+    T: DeserializeOwned,
+    C: Connector,
+{
+    from_developer_metadata_with_options(sheets, spreadsheet_id, key, &Options::default()).await
+}
+
+/// Like [`from_developer_metadata`], with the given options.
+pub async fn from_developer_metadata_with_options<T, C>(
+    sheets: &Sheets<C>,
+    spreadsheet_id: &str,
+    key: &str,
+    options: &Options,
+) -> Result<T>
+where
+    T: DeserializeOwned,
+    C: Connector,
+{
+    from_source_developer_metadata(sheets, spreadsheet_id, key, options).await
+}
+
+#[tokio::test]
+async fn test_developer_metadata() {
+    use crate::source::{fixture_spreadsheet, Fixture};
+    use google_sheets4::api::{DeveloperMetadataLocation, DimensionProperties, DimensionRange};
+    use serde::Deserialize;
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Order {
+        id: String,
+    }
+
+    let tag = |location| DeveloperMetadata {
+        metadata_key: Some("orders".to_owned()),
+        location: Some(location),
+        ..DeveloperMetadata::default()
+    };
+
+    let mut spreadsheet = fixture_spreadsheet(vec![
+        ("Summary", vec![vec!["total"], vec!["2"]]),
+        (
+            "Data",
+            vec![
+                vec!["Inserted by a user"],
+                vec!["id"],
+                vec!["a"],
+                vec!["b"],
+                vec!["notes"],
+            ],
+        ),
+    ]);
+    let sheet = &mut spreadsheet.sheets.as_mut().unwrap()[1];
+    let grid_data = &mut sheet.data.as_mut().unwrap()[0];
+    let mut rows = vec![DimensionProperties::default(); 5];
+    rows[1].developer_metadata = Some(vec![tag(DeveloperMetadataLocation {
+        location_type: Some("ROW".to_owned()),
+        dimension_range: Some(DimensionRange {
+            sheet_id: Some(1),
+            dimension: Some("ROWS".to_owned()),
+            start_index: Some(1),
+            end_index: Some(4),
+        }),
+        ..DeveloperMetadataLocation::default()
+    })]);
+    grid_data.row_metadata = Some(rows);
+
+    let expected = vec![Order { id: "a".to_owned() }, Order { id: "b".to_owned() }];
+
+    let orders: Vec<Order> =
+        from_fetched_developer_metadata(&spreadsheet, "orders", &Options::default()).unwrap();
+    assert_eq!(orders, expected);
+
+    let mut fixture = Fixture::new();
+    fixture.insert("id", spreadsheet.clone());
+    let orders: Vec<Order> =
+        from_source_developer_metadata(&fixture, "id", "orders", &Options::default())
+            .await
+            .unwrap();
+    assert_eq!(orders, expected);
+
+    // Without row metadata, a tag on the spreadsheet names the range.
+    spreadsheet.sheets.as_mut().unwrap()[1]
+        .data
+        .as_mut()
+        .unwrap()[0]
+        .row_metadata = None;
+    spreadsheet.developer_metadata = Some(vec![DeveloperMetadata {
+        metadata_value: Some("Data!A2:A3".to_owned()),
+        ..tag(DeveloperMetadataLocation {
+            spreadsheet: Some(true),
+            ..DeveloperMetadataLocation::default()
+        })
+    }]);
+    let orders: Vec<Order> =
+        from_fetched_developer_metadata(&spreadsheet, "orders", &Options::default()).unwrap();
+    assert_eq!(orders, expected[..1]);
+
+    let err = from_fetched_developer_metadata::<Vec<Order>>(&spreadsheet, "x", &Options::default())
+        .unwrap_err();
+    assert!(matches!(err, Error::UnknownMetadataKey(key) if key == "x"));
+}
+
+#[tokio::test]
+async fn test_search_developer_metadata() {
+    use crate::de::{serve_json, test_hub};
+    use crate::source::fixture_spreadsheet;
+    use google_sheets4::api::DeveloperMetadataLocation;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Order {
+        id: String,
+    }
+
+    // The test server answers every request with the same body, so it holds
+    // both the spreadsheet and the search result.
+    let tag = DeveloperMetadata {
+        metadata_key: Some("orders".to_owned()),
+        location: Some(DeveloperMetadataLocation {
+            sheet_id: Some(0),
+            ..DeveloperMetadataLocation::default()
+        }),
+        ..DeveloperMetadata::default()
+    };
+    let mut spreadsheet = fixture_spreadsheet(vec![("Data", vec![vec!["id"], vec!["a"]])]);
+    spreadsheet.sheets.as_mut().unwrap()[0].developer_metadata = Some(vec![tag.clone()]);
+    let mut body = serde_json::to_value(&spreadsheet).unwrap();
+    body["matchedDeveloperMetadata"] = serde_json::json!([{ "developerMetadata": tag }]);
+    let (addr, requests) = serve_json(body.to_string()).await;
+    let hub = test_hub(addr);
+
+    // Read-only credentials can't search, so by default every sheet's
+    // metadata is read instead.
+    let orders: Vec<Order> = from_developer_metadata(&hub, "abc", "orders")
+        .await
+        .unwrap();
+    assert_eq!(orders, vec![Order { id: "a".to_owned() }]);
+    assert!(requests
+        .lock()
+        .unwrap()
+        .iter()
+        .all(|request| !request.contains("developerMetadata:search")));
+
+    requests.lock().unwrap().clear();
+    let options = Options {
+        search_developer_metadata: true,
+        ..Options::default()
+    };
+    let orders: Vec<Order> = from_developer_metadata_with_options(&hub, "abc", "orders", &options)
+        .await
+        .unwrap();
+    assert_eq!(orders, vec![Order { id: "a".to_owned() }]);
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(3, requests.len());
+    assert!(requests[1].starts_with("/v4/spreadsheets/abc/developerMetadata:search"));
+}
@@ -0,0 +1,161 @@
+use google_sheets4::api::Spreadsheet;
+use google_sheets4::Sheets;
+use serde::de::DeserializeOwned;
+use tracing::instrument;
+
+use crate::connector::Connector;
+use crate::de::from_fetched_spreadsheet;
+use crate::error::{Error, Result};
+use crate::options::Options;
+use crate::range::A1Range;
+use crate::source::{from_source_range, select_range, sheet_title, SheetSource};
+
+// Resolves a named range of the spreadsheet into A1 notation. The API leaves
+// out the sheet id of ranges on the sheet with id 0.
+fn named_range(spreadsheet: &Spreadsheet, name: &str) -> Result<A1Range> {
+    let unknown = || Error::UnknownNamedRange(name.to_owned());
+
+    let range = spreadsheet
+        .named_ranges
+        .iter()
+        .flatten()
+        .find(|named| named.name.as_deref() == Some(name))
+        .and_then(|named| named.range.as_ref())
+        .ok_or_else(unknown)?;
+
+    let title = spreadsheet
+        .sheets
+        .iter()
+        .flatten()
+        .find(|sheet| {
+            let sheet_id = sheet.properties.as_ref().and_then(|p| p.sheet_id);
+            sheet_id.unwrap_or(0) == range.sheet_id.unwrap_or(0)
+        })
+        .and_then(sheet_title)
+        .ok_or(Error::MissingSheet)?;
+
+    Ok(A1Range::from_grid_range(title, range))
+}
+
+/// Deserializes the named range `name`, e.g. `PriceTable`, of an already
+/// fetched spreadsheet. The spreadsheet must include its named ranges and the
+/// grid data of the sheet the range is on.
+#[instrument(skip(spreadsheet, options))]
+pub fn from_fetched_named_range<T>(
+    spreadsheet: &Spreadsheet,
+    name: &str,
+    options: &Options,
+) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let range = named_range(spreadsheet, name)?.to_string();
+
+    from_fetched_spreadsheet(select_range(spreadsheet, &range)?, options)
+}
+
+/// Deserializes the named range `name` of a spreadsheet read from `source`.
+#[instrument(skip(source, options))]
+pub async fn from_source_named_range<T, S>(
+    source: &S,
+    spreadsheet_id: &str,
+    name: &str,
+    options: &Options,
+) -> Result<T>
+where
+    T: DeserializeOwned,
+    S: SheetSource,
+{
+    let metadata = source.spreadsheet(spreadsheet_id).await?;
+    let range = named_range(&metadata, name)?.to_string();
+
+    from_source_range(source, spreadsheet_id, &range, options).await
+}
+
+/// Fetches and deserializes the named range `name` of a spreadsheet.
+pub async fn from_named_range<T, C>(
+    sheets: &Sheets<C>,
+    spreadsheet_id: &str,
+    name: &str,
+) -> Result<T>
+where
+    T: DeserializeOwned,
+    C: Connector,
+{
+    from_named_range_with_options(sheets, spreadsheet_id, name, &Options::default()).await
+}
+
+/// Like [`from_named_range`], with the given options.
+pub async fn from_named_range_with_options<T, C>(
+    sheets: &Sheets<C>,
+    spreadsheet_id: &str,
+    name: &str,
+    options: &Options,
+) -> Result<T>
+where
+    T: DeserializeOwned,
+    C: Connector,
+{
+    from_source_named_range(sheets, spreadsheet_id, name, options).await
+}
+
+#[tokio::test]
+async fn test_named_range() {
+    use crate::source::{fixture_spreadsheet, Fixture};
+    use google_sheets4::api::{GridRange, NamedRange};
+    use serde::Deserialize;
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Price {
+        item: String,
+        price: String,
+    }
+
+    let mut spreadsheet = fixture_spreadsheet(vec![
+        ("Notes", vec![vec!["x"]]),
+        (
+            "Prices",
+            vec![
+                vec!["Price list"],
+                vec![],
+                vec!["item", "price"],
+                vec!["tea", "3"],
+                vec!["total", "3"],
+            ],
+        ),
+    ]);
+    spreadsheet.named_ranges = Some(vec![NamedRange {
+        name: Some("PriceTable".to_owned()),
+        range: Some(GridRange {
+            sheet_id: Some(1),
+            start_row_index: Some(2),
+            end_row_index: Some(4),
+            start_column_index: Some(0),
+            end_column_index: Some(2),
+        }),
+        ..NamedRange::default()
+    }]);
+
+    let expected = vec![Price {
+        item: "tea".to_owned(),
+        price: "3".to_owned(),
+    }];
+
+    let prices: Vec<Price> =
+        from_fetched_named_range(&spreadsheet, "PriceTable", &Options::default()).unwrap();
+    assert_eq!(prices, expected);
+
+    let mut fixture = Fixture::new();
+    fixture.insert("id", spreadsheet);
+    let prices: Vec<Price> =
+        from_source_named_range(&fixture, "id", "PriceTable", &Options::default())
+            .await
+            .unwrap();
+    assert_eq!(prices, expected);
+
+    let err =
+        from_source_named_range::<Vec<Price>, _>(&fixture, "id", "Missing", &Options::default())
+            .await
+            .unwrap_err();
+    assert!(matches!(err, Error::UnknownNamedRange(name) if name == "Missing"));
+}
@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use base64::alphabet;
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use base64::Engine;
+
+use crate::row::Column;
+
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Settings that control how cells are turned into Rust values.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// Separator used to split a single cell into the items of a sequence
+    /// field, e.g. `"red, green, blue"` into a `Vec<String>`.
+    pub delimiter: char,
+
+    /// Whether surrounding whitespace is removed from each sequence item.
+    pub trim_items: bool,
+
+    /// What to do with sequence items that are empty (after trimming).
+    pub empty_items: EmptyItems,
+
+    /// Whether every cell of a merged range takes the value of its top-left
+    /// cell. Sheets only reports a value for the top-left cell, so without
+    /// this the remaining rows of a vertically merged block are blank.
+    pub fill_merged_cells: bool,
+
+    /// Collapses consecutive rows into parent values with a list of child
+    /// rows, see [`Grouping`].
+    pub grouping: Option<Grouping>,
+
+    /// Header of the column whose values key the rows when deserializing a
+    /// whole sheet into a map such as `HashMap<K, Row>`. Defaults to the
+    /// first column.
+    pub map_key: Option<String>,
+
+    /// Text encoding of cells deserialized as bytes, e.g. `serde_bytes::ByteBuf`
+    /// or, with `bytes_arrays`, `[u8; 32]`.
+    pub bytes_encoding: BytesEncoding,
+
+    /// Whether fixed-size arrays read from a single cell, such as `[u8; 32]`,
+    /// are decoded from the cell as bytes instead of split into a delimited
+    /// list. This applies to every array in the row, so leave it off for rows
+    /// with arrays of other types.
+    pub bytes_arrays: bool,
+
+    /// Whether 64-bit and wider integers are parsed from the cell's formatted
+    /// text (ignoring thousands separators) rather than its f64 value. This
+    /// also accepts IDs stored as text cells. Number cells beyond 2^53 whose
+    /// text doesn't match their value are rejected either way.
+    pub integers_from_text: bool,
+
+    /// Column mapping of the row type, usually from [`Options::for_row`].
+    /// Columns are matched by letter or header, required columns are checked
+    /// before any row is read, and cells are checked against the column's
+    /// number format.
+    pub columns: Vec<Column>,
+
+    /// Column positions of struct fields by field name, e.g. `"price"` to
+    /// `"$C"` or `"#3"`, for sheets whose header text changes but whose
+    /// layout doesn't. Fields can also be named after a position directly
+    /// with `#[serde(rename = "$C")]`. Positions are columns of the sheet,
+    /// not of the range read, and a positioned field ignores any header
+    /// that still carries its name.
+    pub positions: HashMap<String, String>,
+
+    /// Whether the header row is checked against the fields of the row
+    /// struct before the first row is read, failing with one error that
+    /// lists every missing, misspelled and unexpected column. Every field
+    /// counts, including `Option` fields. Off by default, since extra
+    /// columns are usually harmless.
+    ///
+    /// The fields are learnt from the first row, so a sheet with a header
+    /// but no data rows is only checked against the declared `columns`
+    /// (see [`Options::for_row`]). With `grouping`, columns that aren't
+    /// fields of the outer row may belong to the child rows and aren't
+    /// reported as unexpected.
+    pub validate_headers: bool,
+
+    /// What to do with blank cells in the header row.
+    pub blank_headers: BlankHeaders,
+
+    /// What to do with header cells holding a number, bool or error instead
+    /// of text, such as a year.
+    pub non_text_headers: NonTextHeaders,
+
+    /// Whether rows hidden by the user are left out.
+    pub skip_hidden_rows: bool,
+
+    /// Whether rows hidden by a filter are left out, so only the rows
+    /// currently shown by the sheet's filter are read.
+    pub skip_filtered_rows: bool,
+
+    /// Whether rows whose first cell starts with `#` are left out.
+    pub skip_comment_rows: bool,
+
+    /// Whether rows without any values are left out rather than read as rows
+    /// of blank cells. Sheet-level maps always leave them out.
+    pub skip_blank_rows: bool,
+
+    /// Whether columns hidden by the user are left out of the header, so no
+    /// field is read from them. They often hold stale helper data. Columns
+    /// mapped by letter or position are read regardless.
+    pub skip_hidden_columns: bool,
+
+    /// Whether columns with a dropdown (a "one of list" data validation rule)
+    /// are checked against the variants of the enum they're read into, or of
+    /// the items of a list for multi-select dropdowns. The first time such a
+    /// column is read, dropdown values the enum can't represent and variants
+    /// missing from the dropdown are reported.
+    pub validate_dropdowns: bool,
+
+    /// Whether ranges tagged with developer metadata are found with the
+    /// Sheets API's developer metadata search, in one request, rather than
+    /// by reading the metadata of every sheet. The search needs the
+    /// read-write `spreadsheets` scope, so it's off by default.
+    pub search_developer_metadata: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            delimiter: ',',
+            trim_items: true,
+            empty_items: EmptyItems::Skip,
+            fill_merged_cells: false,
+            grouping: None,
+            map_key: None,
+            bytes_encoding: BytesEncoding::Base64,
+            bytes_arrays: false,
+            integers_from_text: false,
+            columns: Vec::new(),
+            positions: HashMap::new(),
+            validate_headers: false,
+            blank_headers: BlankHeaders::Skip,
+            non_text_headers: NonTextHeaders::Text,
+            skip_hidden_rows: false,
+            skip_filtered_rows: false,
+            skip_comment_rows: false,
+            skip_blank_rows: false,
+            skip_hidden_columns: false,
+            validate_dropdowns: false,
+            search_developer_metadata: false,
+        }
+    }
+}
+
+/// Handling of empty items when splitting a cell into a sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmptyItems {
+    /// Drop empty items, so `"a,,b"` yields `["a", "b"]`.
+    Skip,
+
+    /// Keep empty items, so `"a,,b"` yields `["a", "", "b"]`.
+    Keep,
+}
+
+/// Handling of blank cells in the header row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlankHeaders {
+    /// Leave the column out, so no field is read from it.
+    Skip,
+
+    /// Fail with [`Error::BlankHeader`](crate::Error::BlankHeader).
+    Error,
+}
+
+/// Handling of header cells that don't hold text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonTextHeaders {
+    /// Match the column by the formatted text of the cell, e.g. `"2024"`.
+    Text,
+
+    /// Fail with [`Error::HeaderMustBeString`](crate::Error::HeaderMustBeString).
+    Error,
+}
+
+/// Text encoding used for binary values stored in cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BytesEncoding {
+    /// Standard base64 alphabet, with or without padding.
+    Base64,
+
+    /// Hexadecimal digits in either case.
+    Hex,
+}
+
+impl BytesEncoding {
+    pub(crate) fn decode(self, value: &str) -> Option<Vec<u8>> {
+        let value = value.trim();
+
+        match self {
+            BytesEncoding::Base64 => BASE64.decode(value).ok(),
+            BytesEncoding::Hex => hex::decode(value).ok(),
+        }
+    }
+}
+
+impl fmt::Display for BytesEncoding {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BytesEncoding::Base64 => formatter.write_str("base64"),
+            BytesEncoding::Hex => formatter.write_str("hex"),
+        }
+    }
+}
+
+/// Parent/child grouping of rows, e.g. an invoice sheet where the order
+/// columns are only filled in on the first row of each order.
+///
+/// A row starts a new parent unless every key column is either blank or equal
+/// to the current parent's value. The parent is deserialized from its first
+/// row, and the field named `children` receives every row of the group
+/// (including the first) deserialized as the child type.
+#[derive(Debug, Clone)]
+pub struct Grouping {
+    /// Header names of the columns identifying a parent.
+    pub key_columns: Vec<String>,
+
+    /// Name of the parent field that holds the child rows.
+    pub children: String,
+}
@@ -0,0 +1,311 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+use google_sheets4::api::{GridData, GridRange, RowData};
+
+/// A range in A1 notation, such as `Sheet1`, `'Price List'!A2:D` or `B:B`.
+///
+/// Rows and columns are 0-based with exclusive ends, like `GridRange`;
+/// `None` leaves that side of the range unbounded.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct A1Range {
+    pub sheet: Option<String>,
+    pub start_row: Option<u32>,
+    pub end_row: Option<u32>,
+    pub start_column: Option<u32>,
+    pub end_column: Option<u32>,
+}
+
+impl A1Range {
+    /// The whole of the named sheet.
+    pub fn sheet(title: &str) -> Self {
+        A1Range {
+            sheet: Some(title.to_owned()),
+            ..A1Range::default()
+        }
+    }
+
+    /// The equivalent of `range` on the sheet with the given title.
+    pub fn from_grid_range(title: &str, range: &GridRange) -> Self {
+        let index = |v: Option<i32>| v.map(|v| v.max(0) as u32);
+
+        A1Range {
+            sheet: Some(title.to_owned()),
+            start_row: index(range.start_row_index),
+            end_row: index(range.end_row_index),
+            start_column: index(range.start_column_index),
+            end_column: index(range.end_column_index),
+        }
+    }
+
+    /// The equivalent `GridRange` on the sheet with the given id.
+    pub fn to_grid_range(&self, sheet_id: i32) -> GridRange {
+        let index = |v: Option<u32>| v.map(|v| v as i32);
+
+        GridRange {
+            sheet_id: Some(sheet_id),
+            start_row_index: index(self.start_row),
+            end_row_index: index(self.end_row),
+            start_column_index: index(self.start_column),
+            end_column_index: index(self.end_column),
+        }
+    }
+}
+
+impl FromStr for A1Range {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::InvalidRange(s.to_owned());
+
+        let (sheet, cells) = match s.rsplit_once('!') {
+            Some((sheet, cells)) => (Some(unquote_sheet(sheet)), Some(cells)),
+            // Without a '!' the text is either a cell range or a sheet title.
+            None if parse_cells(s).is_some() => (None, Some(s)),
+            None => (Some(unquote_sheet(s)), None),
+        };
+
+        let mut range = A1Range {
+            sheet,
+            ..A1Range::default()
+        };
+
+        if let Some(cells) = cells {
+            let ((start_column, start_row), (end_column, end_row)) =
+                parse_cells(cells).ok_or_else(invalid)?;
+
+            range.start_row = start_row;
+            range.end_row = end_row.map(|v| v + 1);
+            range.start_column = start_column;
+            range.end_column = end_column.map(|v| v + 1);
+        }
+
+        Ok(range)
+    }
+}
+
+impl Display for A1Range {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let bounded = self.start_row.is_some()
+            || self.end_row.is_some()
+            || self.start_column.is_some()
+            || self.end_column.is_some();
+
+        if let Some(sheet) = &self.sheet {
+            formatter.write_fmt(format_args!("'{}'", sheet.replace('\'', "''")))?;
+
+            if !bounded {
+                return Ok(());
+            }
+
+            formatter.write_str("!")?;
+        }
+
+        // Open-ended rows need a column on both ends, as in `A5:ZZZ`.
+        let rows = self.start_row.is_some() || self.end_row.is_some();
+        let columns = self.start_column.is_some()
+            || self.end_column.is_some()
+            || (self.start_row.is_some() && self.end_row.is_none());
+
+        let mut start = String::new();
+        let mut end = String::new();
+
+        if columns {
+            start.push_str(&column_letters(self.start_column.unwrap_or(0)));
+            end.push_str(&column_letters(
+                self.end_column.map_or(LAST_COLUMN, |v| v.saturating_sub(1)),
+            ));
+        }
+
+        if rows {
+            start.push_str(&(self.start_row.unwrap_or(0) + 1).to_string());
+        }
+
+        if let Some(end_row) = self.end_row {
+            end.push_str(&end_row.to_string());
+        }
+
+        formatter.write_fmt(format_args!("{}:{}", start, end))
+    }
+}
+
+// Index of column ZZZ, the last column a sheet can have.
+const LAST_COLUMN: u32 = 18277;
+const LAST_COLUMN_NUMBER: u32 = LAST_COLUMN + 1;
+
+/// Converts a 0-based column index into its letters, e.g. 27 into `AB`.
+pub fn column_letters(mut index: u32) -> String {
+    let mut letters = Vec::new();
+
+    loop {
+        letters.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+
+    letters.iter().rev().map(|&b| b as char).collect()
+}
+
+/// Converts column letters such as `AB` into a 0-based column index. Sheets
+/// end at column `ZZZ`, so longer names are rejected.
+pub fn column_index(letters: &str) -> Option<u32> {
+    if letters.is_empty() || letters.len() > 3 || !letters.bytes().all(|b| b.is_ascii_alphabetic())
+    {
+        return None;
+    }
+
+    letters
+        .bytes()
+        .try_fold(0u32, |acc, b| {
+            acc.checked_mul(26)?
+                .checked_add(u32::from(b.to_ascii_uppercase() - b'A') + 1)
+        })
+        .map(|v| v - 1)
+}
+
+/// Converts a column position into a 0-based column index: `$` followed by
+/// column letters (`"$C"`) or `#` followed by a 1-based column number
+/// (`"#3"`).
+pub fn column_position(position: &str) -> Option<usize> {
+    if let Some(letters) = position.strip_prefix('$') {
+        return column_index(letters).map(|idx| idx as usize);
+    }
+
+    match position.strip_prefix('#')?.parse::<u32>().ok()? {
+        n @ 1..=LAST_COLUMN_NUMBER => Some(n as usize - 1),
+        _ => None,
+    }
+}
+
+fn unquote_sheet(sheet: &str) -> String {
+    match sheet.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')) {
+        Some(quoted) => quoted.replace("''", "'"),
+        None => sheet.to_owned(),
+    }
+}
+
+type Cell = (Option<u32>, Option<u32>);
+
+// Parses `A1`, `A` or `1` into a 0-based (column, row).
+fn parse_cell(cell: &str) -> Option<Cell> {
+    let digits = cell
+        .find(|c: char| c.is_ascii_digit())
+        .unwrap_or(cell.len());
+    let (letters, digits) = cell.split_at(digits);
+
+    let column = match letters {
+        "" => None,
+        letters => Some(column_index(letters)?),
+    };
+    let row = match digits {
+        "" => None,
+        digits => Some(digits.parse::<u32>().ok()?.checked_sub(1)?),
+    };
+
+    if column.is_none() && row.is_none() {
+        return None;
+    }
+
+    Some((column, row))
+}
+
+// Parses `A1:C10`, `A:C`, `2:10` or a single cell such as `B2` into
+// inclusive bounds.
+fn parse_cells(cells: &str) -> Option<(Cell, Cell)> {
+    match cells.split_once(':') {
+        Some((start, end)) => Some((parse_cell(start)?, parse_cell(end)?)),
+        None => match parse_cell(cells)? {
+            cell @ (Some(_), Some(_)) => Some((cell, cell)),
+            _ => None,
+        },
+    }
+}
+
+/// Copies the part of `grid_data` inside `range` (sheet coordinates), along
+/// with the matching row and column metadata.
+pub fn slice_grid_data(grid_data: &GridData, range: &GridRange) -> GridData {
+    let start_row = grid_data.start_row.unwrap_or(0);
+    let start_column = grid_data.start_column.unwrap_or(0);
+
+    let rows = local_bounds(range.start_row_index, range.end_row_index, start_row);
+    let columns = local_bounds(
+        range.start_column_index,
+        range.end_column_index,
+        start_column,
+    );
+
+    GridData {
+        start_row: Some(start_row + rows.0 as i32),
+        start_column: Some(start_column + columns.0 as i32),
+        row_data: grid_data.row_data.as_ref().map(|row_data| {
+            slice(row_data, rows)
+                .into_iter()
+                .map(|row| RowData {
+                    values: row.values.as_ref().map(|values| slice(values, columns)),
+                })
+                .collect()
+        }),
+        row_metadata: grid_data.row_metadata.as_ref().map(|v| slice(v, rows)),
+        column_metadata: grid_data
+            .column_metadata
+            .as_ref()
+            .map(|v| slice(v, columns)),
+    }
+}
+
+fn slice<T: Clone>(v: &[T], (start, end): (usize, Option<usize>)) -> Vec<T> {
+    let end = end.unwrap_or(usize::MAX).min(v.len());
+
+    v[start.min(end)..end].to_vec()
+}
+
+// Converts sheet-level [start, end) bounds into grid-local ones.
+fn local_bounds(start: Option<i32>, end: Option<i32>, offset: i32) -> (usize, Option<usize>) {
+    let local = |v: i32| (v - offset).max(0) as usize;
+
+    (local(start.unwrap_or(0)), end.map(local))
+}
+
+#[test]
+fn test_parse_a1_range() {
+    let range: A1Range = "'Bob''s Sheet'!B2:D10".parse().unwrap();
+
+    assert_eq!(
+        A1Range {
+            sheet: Some("Bob's Sheet".to_owned()),
+            start_row: Some(1),
+            end_row: Some(10),
+            start_column: Some(1),
+            end_column: Some(4),
+        },
+        range
+    );
+    assert_eq!("'Bob''s Sheet'!B2:D10", range.to_string());
+
+    let range: A1Range = "A:AB".parse().unwrap();
+    assert_eq!((Some(0), Some(28)), (range.start_column, range.end_column));
+    assert_eq!((None, None), (range.start_row, range.end_row));
+
+    let range: A1Range = "Sheet1!5:104".parse().unwrap();
+    assert_eq!((Some(4), Some(104)), (range.start_row, range.end_row));
+    assert_eq!("'Sheet1'!5:104", range.to_string());
+
+    let range = A1Range {
+        start_row: Some(1),
+        start_column: Some(2),
+        ..A1Range::sheet("S")
+    };
+    assert_eq!("'S'!C2:ZZZ", range.to_string());
+
+    assert_eq!(A1Range::sheet("Prices"), "Prices".parse().unwrap());
+    assert_eq!("'Prices'", A1Range::sheet("Prices").to_string());
+    assert!("Sheet1!A0:B2".parse::<A1Range>().is_err());
+
+    assert_eq!(Some(2), column_position("$C"));
+    assert_eq!(Some(2), column_position("#3"));
+    assert_eq!(None, column_position("#0"));
+    assert_eq!(None, column_position("C"));
+}
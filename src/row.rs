@@ -0,0 +1,72 @@
+use crate::options::Options;
+
+/// Describes how the fields of a row type map onto sheet columns. Usually
+/// derived with `#[derive(SheetRow)]` (requires the `derive` feature).
+pub trait SheetRow {
+    fn columns() -> Vec<Column>;
+}
+
+/// Where a struct field is read from and what the column must look like.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Column {
+    /// Field name as serde sees it, i.e. after `#[serde(rename)]`.
+    pub field: &'static str,
+
+    /// Header text of the column, if it differs from the field name.
+    pub header: Option<&'static str>,
+
+    /// Column letter, e.g. `"C"`, for columns matched by position rather
+    /// than by header.
+    pub letter: Option<&'static str>,
+
+    /// Whether the column must be present in the header row.
+    pub required: bool,
+
+    /// Number format non-blank cells of the column must have.
+    pub format: Option<ColumnFormat>,
+}
+
+impl Column {
+    /// The name the column is known by in error messages.
+    pub fn name(&self) -> &'static str {
+        self.letter.or(self.header).unwrap_or(self.field)
+    }
+}
+
+/// The number format types of the Sheets API (`NumberFormat.type`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnFormat {
+    Text,
+    Number,
+    Percent,
+    Currency,
+    Date,
+    Time,
+    DateTime,
+    Scientific,
+}
+
+impl ColumnFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ColumnFormat::Text => "TEXT",
+            ColumnFormat::Number => "NUMBER",
+            ColumnFormat::Percent => "PERCENT",
+            ColumnFormat::Currency => "CURRENCY",
+            ColumnFormat::Date => "DATE",
+            ColumnFormat::Time => "TIME",
+            ColumnFormat::DateTime => "DATE_TIME",
+            ColumnFormat::Scientific => "SCIENTIFIC",
+        }
+    }
+}
+
+impl Options {
+    /// Default options with the column mapping of `T`.
+    pub fn for_row<T: SheetRow>() -> Self {
+        Options {
+            columns: T::columns(),
+            ..Options::default()
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::de::MAX_SAFE_INTEGER;
+use crate::error::{Error, Result};
+use google_sheets4::api::{CellData, GridData};
+
+/// The Rust type inferred for the values of a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    Bool,
+    Integer,
+    Float,
+    Text,
+}
+
+impl ColumnType {
+    pub fn rust_type(self) -> &'static str {
+        match self {
+            ColumnType::Bool => "bool",
+            ColumnType::Integer => "i64",
+            ColumnType::Float => "f64",
+            ColumnType::Text => "String",
+        }
+    }
+
+    // The narrowest type that can hold values of both types.
+    fn merge(self, other: ColumnType) -> ColumnType {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (ColumnType::Integer, ColumnType::Float) | (ColumnType::Float, ColumnType::Integer) => {
+                ColumnType::Float
+            }
+            _ => ColumnType::Text,
+        }
+    }
+}
+
+/// A column of a sheet as seen by the deserializer: its header, the struct
+/// field it maps to, and the type of its values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnSchema {
+    pub header: String,
+    pub field: String,
+    pub ty: ColumnType,
+    // Whether any row leaves the column blank.
+    pub optional: bool,
+}
+
+/// Infers the columns of a sheet from its header row and the values below it.
+/// Columns without a header are left out, as are completely blank rows.
+/// Headers are taken from the formatted text of the cell, as the deserializer
+/// reads them by default, so a year header `2024` becomes a `column_2024`
+/// field renamed to `"2024"`.
+pub fn infer_schema(grid_data: &GridData) -> Result<Vec<ColumnSchema>> {
+    let mut rows = grid_data
+        .row_data
+        .as_deref()
+        .ok_or(Error::ZeroRows)?
+        .iter()
+        .map(|v| v.values.as_deref().unwrap_or_default());
+
+    let header = rows.next().ok_or(Error::ZeroRows)?;
+    let rows: Vec<_> = rows
+        .filter(|row| row.iter().any(|cell| cell.effective_value.is_some()))
+        .collect();
+
+    let mut fields = HashSet::new();
+
+    let columns = header
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, cell)| Some((idx, cell.formatted_value.as_deref()?)))
+        .map(|(idx, header)| {
+            let mut ty = None;
+            let mut optional = rows.is_empty();
+
+            for row in &rows {
+                match row.get(idx).and_then(cell_type) {
+                    Some(cell_ty) => {
+                        ty = Some(ty.map_or(cell_ty, |ty: ColumnType| ty.merge(cell_ty)))
+                    }
+                    None => optional = true,
+                }
+            }
+
+            ColumnSchema {
+                header: header.to_owned(),
+                field: unique_field_name(header, &mut fields),
+                ty: ty.unwrap_or(ColumnType::Text),
+                optional,
+            }
+        })
+        .collect();
+
+    Ok(columns)
+}
+
+/// Renders a `#[derive(Deserialize)]` struct for the given columns.
+pub fn generate_struct(name: &str, columns: &[ColumnSchema]) -> String {
+    let mut out = String::new();
+
+    out.push_str("#[derive(Debug, Deserialize)]\n");
+    let _ = writeln!(out, "pub struct {} {{", name);
+
+    for column in columns {
+        if column.field.trim_start_matches("r#") != column.header {
+            let _ = writeln!(out, "    #[serde(rename = {:?})]", column.header);
+        }
+
+        let ty = if column.optional {
+            format!("Option<{}>", column.ty.rust_type())
+        } else {
+            column.ty.rust_type().to_owned()
+        };
+
+        let _ = writeln!(out, "    pub {}: {},", column.field, ty);
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+// The type a single cell deserializes to, or `None` for a blank cell. Dates
+// and times are read as their formatted text.
+fn cell_type(cell: &CellData) -> Option<ColumnType> {
+    let value = cell.effective_value.as_ref()?;
+
+    if value.bool_value.is_some() {
+        return Some(ColumnType::Bool);
+    }
+
+    if let Some(number) = value.number_value {
+        let number_format = cell
+            .effective_format
+            .as_ref()
+            .and_then(|v| v.number_format.as_ref())
+            .and_then(|v| v.type_.as_deref());
+
+        return Some(match number_format {
+            Some("DATE" | "TIME" | "DATE_TIME") => ColumnType::Text,
+            _ if number.fract() == 0.0 && number.abs() <= MAX_SAFE_INTEGER => ColumnType::Integer,
+            _ => ColumnType::Float,
+        });
+    }
+
+    if value.string_value.is_some() || value.formula_value.is_some() || value.error_value.is_some()
+    {
+        return Some(ColumnType::Text);
+    }
+
+    None
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while",
+    "abstract", "become", "box", "do", "final", "macro", "override", "priv", "try", "typeof",
+    "unsized", "virtual", "yield",
+];
+
+// Keywords that can't be raw identifiers either, so they get a suffix.
+const PATH_KEYWORDS: &[&str] = &["self", "super", "crate"];
+
+// Turns header text into a snake_case identifier that hasn't been used yet,
+// e.g. "Unit Price" into `unit_price`.
+fn unique_field_name(header: &str, used: &mut HashSet<String>) -> String {
+    let mut name = String::new();
+
+    for c in header.chars() {
+        if c.is_alphanumeric() {
+            if c.is_uppercase() && name.chars().last().is_some_and(|last| last.is_lowercase()) {
+                name.push('_');
+            }
+            name.extend(c.to_lowercase());
+        } else if !name.is_empty() && !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+
+    let mut name = name.trim_end_matches('_').to_owned();
+
+    if name.is_empty() {
+        name.push_str("column");
+    } else if name.starts_with(|c: char| c.is_numeric()) {
+        name.insert_str(0, "column_");
+    } else if PATH_KEYWORDS.contains(&name.as_str()) {
+        name.push('_');
+    } else if KEYWORDS.contains(&name.as_str()) {
+        name.insert_str(0, "r#");
+    }
+
+    let mut unique = name.clone();
+    let mut suffix = 2;
+
+    while !used.insert(unique.clone()) {
+        unique = format!("{}_{}", name, suffix);
+        suffix += 1;
+    }
+
+    unique
+}
+
+#[test]
+fn test_infer_schema() {
+    use crate::de::{grid_data, number_cell, string_cell};
+    use google_sheets4::api::ExtendedValue;
+
+    let bool_cell = |b| CellData {
+        effective_value: Some(ExtendedValue {
+            bool_value: Some(b),
+            ..ExtendedValue::default()
+        }),
+        ..CellData::default()
+    };
+
+    let data = grid_data(vec![
+        vec![
+            string_cell("Name"),
+            string_cell("Unit Price"),
+            string_cell("Qty"),
+            string_cell("In Stock"),
+            string_cell("type"),
+            string_cell("Name"),
+        ],
+        vec![
+            string_cell("Apple"),
+            number_cell(0.5),
+            number_cell(3.0),
+            bool_cell(true),
+            string_cell("fruit"),
+            string_cell("x"),
+        ],
+        vec![
+            string_cell("Pear"),
+            number_cell(1.0),
+            CellData::default(),
+            bool_cell(false),
+            number_cell(2.0),
+            string_cell("y"),
+        ],
+        vec![CellData::default()],
+    ]);
+
+    let columns = infer_schema(&data).unwrap();
+
+    let summary: Vec<_> = columns
+        .iter()
+        .map(|c| (c.field.as_str(), c.ty, c.optional))
+        .collect();
+
+    assert_eq!(
+        vec![
+            ("name", ColumnType::Text, false),
+            ("unit_price", ColumnType::Float, false),
+            ("qty", ColumnType::Integer, true),
+            ("in_stock", ColumnType::Bool, false),
+            ("r#type", ColumnType::Text, false),
+            ("name_2", ColumnType::Text, false),
+        ],
+        summary
+    );
+
+    assert_eq!(
+        "#[derive(Debug, Deserialize)]
+pub struct Row {
+    #[serde(rename = \"Name\")]
+    pub name: String,
+    #[serde(rename = \"Unit Price\")]
+    pub unit_price: f64,
+    #[serde(rename = \"Qty\")]
+    pub qty: Option<i64>,
+}
+",
+        generate_struct("Row", &columns[..3])
+    );
+
+    let mut used = HashSet::new();
+    let names: Vec<_> = ["Self", "super", "Crate"]
+        .iter()
+        .map(|header| unique_field_name(header, &mut used))
+        .collect();
+    assert_eq!(vec!["self_", "super_", "crate_"], names);
+}
+
+#[test]
+fn test_infer_schema_numeric_header() {
+    use crate::de::{grid_data, number_cell, string_cell};
+    use crate::from_grid_data;
+    use serde::Deserialize;
+
+    let data = grid_data(vec![
+        vec![string_cell("name"), number_cell(2024.0)],
+        vec![string_cell("Apple"), number_cell(3.0)],
+    ]);
+
+    let columns = infer_schema(&data).unwrap();
+    assert_eq!(
+        "#[derive(Debug, Deserialize)]
+pub struct Row {
+    pub name: String,
+    #[serde(rename = \"2024\")]
+    pub column_2024: i64,
+}
+",
+        generate_struct("Row", &columns)
+    );
+
+    #[derive(Debug, Deserialize)]
+    pub struct Row {
+        #[allow(dead_code)]
+        pub name: String,
+        #[serde(rename = "2024")]
+        pub column_2024: i64,
+    }
+
+    let rows: Vec<Row> = from_grid_data(&data).unwrap();
+    assert_eq!(rows[0].column_2024, 3);
+}
@@ -0,0 +1,476 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+
+use crate::connector::Connector;
+use crate::de::from_fetched_spreadsheet;
+use crate::error::{Error, Result};
+use crate::fields::field_mask;
+use crate::options::Options;
+use crate::range::{slice_grid_data, A1Range};
+use google_sheets4::api::{
+    DataFilter, DeveloperMetadata, DeveloperMetadataLookup, Scope, SearchDeveloperMetadataRequest,
+    Sheet, Spreadsheet,
+};
+use google_sheets4::Sheets;
+use serde::de::DeserializeOwned;
+use tracing::instrument;
+
+/// Somewhere spreadsheets can be read from: the Sheets API itself, or a
+/// stand-in for it in tests and offline tools.
+pub trait SheetSource {
+    /// Fetches the spreadsheet's metadata (properties, sheets, named ranges)
+    /// without any grid data.
+    fn spreadsheet(&self, spreadsheet_id: &str)
+        -> impl Future<Output = Result<Spreadsheet>> + Send;
+
+    /// Fetches the grid data of `range`, given in A1 notation such as
+    /// `Sheet1` or `'Price List'!A1:D100`. The result holds the addressed
+    /// sheet only. `fields` is the response field mask, see [`field_mask`];
+    /// sources that hold everything in memory may ignore it.
+    fn grid_data(
+        &self,
+        spreadsheet_id: &str,
+        range: &str,
+        fields: &str,
+    ) -> impl Future<Output = Result<Spreadsheet>> + Send;
+
+    /// A token that changes whenever the spreadsheet does, used by
+    /// [`CachedSource`](crate::CachedSource) to revalidate expired entries.
+    /// Sources that can't tell return `None`.
+    fn revision(
+        &self,
+        _spreadsheet_id: &str,
+    ) -> impl Future<Output = Result<Option<String>>> + Send {
+        async { Ok(None) }
+    }
+
+    /// Finds the developer metadata with the key `key`, wherever it's
+    /// attached, in one request. Only used with
+    /// [`Options::search_developer_metadata`]. Sources that can't search
+    /// return `None`, and the metadata is read from the grid data of every
+    /// sheet instead.
+    fn search_developer_metadata(
+        &self,
+        _spreadsheet_id: &str,
+        _key: &str,
+    ) -> impl Future<Output = Result<Option<Vec<DeveloperMetadata>>>> + Send {
+        async { Ok(None) }
+    }
+}
+
+impl<C> SheetSource for Sheets<C>
+where
+    C: Connector,
+{
+    async fn spreadsheet(&self, spreadsheet_id: &str) -> Result<Spreadsheet> {
+        let (_, spreadsheet) = self.spreadsheets().get(spreadsheet_id).doit().await?;
+
+        Ok(spreadsheet)
+    }
+
+    async fn grid_data(
+        &self,
+        spreadsheet_id: &str,
+        range: &str,
+        fields: &str,
+    ) -> Result<Spreadsheet> {
+        let (_, spreadsheet) = self
+            .spreadsheets()
+            .get(spreadsheet_id)
+            .add_ranges(range)
+            .include_grid_data(true)
+            .param("fields", fields)
+            .doit()
+            .await?;
+
+        Ok(spreadsheet)
+    }
+
+    // The Drive version of the spreadsheet file, which goes up with every
+    // change to it. The Sheets API itself has no change token.
+    async fn revision(&self, spreadsheet_id: &str) -> Result<Option<String>> {
+        drive_version(self, DRIVE_FILES_URL, spreadsheet_id).await
+    }
+
+    // The search endpoint doesn't accept the read-only scopes, so this asks
+    // for the spreadsheets scope rather than its default, full Drive access.
+    async fn search_developer_metadata(
+        &self,
+        spreadsheet_id: &str,
+        key: &str,
+    ) -> Result<Option<Vec<DeveloperMetadata>>> {
+        let request = SearchDeveloperMetadataRequest {
+            data_filters: Some(vec![DataFilter {
+                developer_metadata_lookup: Some(DeveloperMetadataLookup {
+                    metadata_key: Some(key.to_owned()),
+                    ..DeveloperMetadataLookup::default()
+                }),
+                ..DataFilter::default()
+            }]),
+        };
+
+        let (_, response) = self
+            .spreadsheets()
+            .developer_metadata_search(request, spreadsheet_id)
+            .add_scope(Scope::Spreadsheet)
+            .doit()
+            .await?;
+
+        Ok(Some(
+            response
+                .matched_developer_metadata
+                .into_iter()
+                .flatten()
+                .filter_map(|matched| matched.developer_metadata)
+                .collect(),
+        ))
+    }
+}
+
+const DRIVE_FILES_URL: &str = "https://www.googleapis.com/drive/v3/files/";
+
+// Reads the `version` of a file from the Drive API at `files_url`, with the
+// hub's client and a token for the scope `spreadsheets.get` uses by default.
+pub(crate) async fn drive_version<C>(
+    sheets: &Sheets<C>,
+    files_url: &str,
+    spreadsheet_id: &str,
+) -> Result<Option<String>>
+where
+    C: Connector,
+{
+    use google_sheets4::hyper::header::AUTHORIZATION;
+    use google_sheets4::hyper::{body, Body, Request};
+    use google_sheets4::Error as ApiError;
+
+    #[derive(serde::Deserialize)]
+    struct File {
+        version: Option<String>,
+    }
+
+    let token = sheets
+        .auth
+        .get_token(&[Scope::DriveReadonly.as_ref()])
+        .await
+        .map_err(ApiError::MissingToken)?;
+
+    let mut request = Request::get(format!(
+        "{}{}?fields=version&supportsAllDrives=true",
+        files_url, spreadsheet_id
+    ));
+    if let Some(token) = token {
+        request = request.header(AUTHORIZATION, format!("Bearer {}", token));
+    }
+    let request = request
+        .body(Body::empty())
+        .map_err(|_| Error::UnknownSpreadsheet(spreadsheet_id.to_owned()))?;
+
+    let response = sheets
+        .client
+        .request(request)
+        .await
+        .map_err(ApiError::HttpError)?;
+    if !response.status().is_success() {
+        return Err(ApiError::Failure(response).into());
+    }
+
+    let json = body::to_bytes(response.into_body())
+        .await
+        .map_err(ApiError::HttpError)?;
+    let file: File = serde_json::from_slice(&json)?;
+
+    Ok(file.version)
+}
+
+/// Spreadsheets held in memory, e.g. responses captured with grid data.
+#[derive(Debug, Clone, Default)]
+pub struct Fixture {
+    spreadsheets: HashMap<String, Spreadsheet>,
+}
+
+impl Fixture {
+    pub fn new() -> Self {
+        Fixture::default()
+    }
+
+    pub fn insert(&mut self, spreadsheet_id: &str, spreadsheet: Spreadsheet) {
+        self.spreadsheets
+            .insert(spreadsheet_id.to_owned(), spreadsheet);
+    }
+
+    fn get(&self, spreadsheet_id: &str) -> Result<&Spreadsheet> {
+        self.spreadsheets
+            .get(spreadsheet_id)
+            .ok_or_else(|| Error::UnknownSpreadsheet(spreadsheet_id.to_owned()))
+    }
+}
+
+impl SheetSource for Fixture {
+    async fn spreadsheet(&self, spreadsheet_id: &str) -> Result<Spreadsheet> {
+        Ok(without_grid_data(self.get(spreadsheet_id)?))
+    }
+
+    async fn grid_data(
+        &self,
+        spreadsheet_id: &str,
+        range: &str,
+        _fields: &str,
+    ) -> Result<Spreadsheet> {
+        select_range(self.get(spreadsheet_id)?, range)
+    }
+}
+
+/// Spreadsheets stored as JSON files named `<spreadsheet id>.json` in a
+/// directory, in the format of a `spreadsheets.get` response with grid data.
+#[derive(Debug, Clone)]
+pub struct JsonDir {
+    path: PathBuf,
+}
+
+impl JsonDir {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        JsonDir { path: path.into() }
+    }
+
+    fn file(&self, spreadsheet_id: &str) -> PathBuf {
+        self.path.join(format!("{}.json", spreadsheet_id))
+    }
+
+    async fn load(&self, spreadsheet_id: &str) -> Result<Spreadsheet> {
+        let json = tokio::fs::read(self.file(spreadsheet_id)).await?;
+
+        Ok(serde_json::from_slice(&json)?)
+    }
+}
+
+impl SheetSource for JsonDir {
+    async fn spreadsheet(&self, spreadsheet_id: &str) -> Result<Spreadsheet> {
+        Ok(without_grid_data(&self.load(spreadsheet_id).await?))
+    }
+
+    async fn grid_data(
+        &self,
+        spreadsheet_id: &str,
+        range: &str,
+        _fields: &str,
+    ) -> Result<Spreadsheet> {
+        select_range(&self.load(spreadsheet_id).await?, range)
+    }
+
+    // The file's modification time.
+    async fn revision(&self, spreadsheet_id: &str) -> Result<Option<String>> {
+        let modified = tokio::fs::metadata(self.file(spreadsheet_id))
+            .await?
+            .modified()?;
+
+        Ok(modified
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()
+            .map(|v| v.as_nanos().to_string()))
+    }
+}
+
+fn without_grid_data(spreadsheet: &Spreadsheet) -> Spreadsheet {
+    let mut spreadsheet = spreadsheet.clone();
+
+    for sheet in spreadsheet.sheets.iter_mut().flatten() {
+        sheet.data = None;
+    }
+
+    spreadsheet
+}
+
+// Mirrors what the API returns for `ranges=<range>&includeGridData=true`.
+pub(crate) fn select_range(spreadsheet: &Spreadsheet, range: &str) -> Result<Spreadsheet> {
+    let range: A1Range = range.parse()?;
+
+    let sheet = spreadsheet
+        .sheets
+        .iter()
+        .flatten()
+        .find(|sheet| match &range.sheet {
+            Some(title) => sheet_title(sheet) == Some(title),
+            None => true,
+        })
+        .ok_or(Error::MissingSheet)?;
+
+    let sheet_id = sheet
+        .properties
+        .as_ref()
+        .and_then(|p| p.sheet_id)
+        .unwrap_or(0);
+    let grid_range = range.to_grid_range(sheet_id);
+
+    let sheet = Sheet {
+        data: sheet.data.as_ref().map(|data| {
+            data.iter()
+                .map(|grid_data| slice_grid_data(grid_data, &grid_range))
+                .collect()
+        }),
+        ..sheet.clone()
+    };
+
+    Ok(Spreadsheet {
+        sheets: Some(vec![sheet]),
+        ..spreadsheet.clone()
+    })
+}
+
+pub(crate) fn sheet_title(sheet: &Sheet) -> Option<&String> {
+    sheet.properties.as_ref().and_then(|p| p.title.as_ref())
+}
+
+/// Deserializes the first sheet of a spreadsheet read from `source`.
+#[instrument(skip(source, options))]
+pub async fn from_source<T, S>(source: &S, spreadsheet_id: &str, options: &Options) -> Result<T>
+where
+    T: DeserializeOwned,
+    S: SheetSource,
+{
+    let metadata = source.spreadsheet(spreadsheet_id).await?;
+
+    let title = metadata
+        .sheets
+        .as_deref()
+        .and_then(|sheets| sheets.first())
+        .and_then(sheet_title)
+        .ok_or(Error::MissingSheet)?;
+
+    let range = A1Range::sheet(title).to_string();
+
+    from_source_range(source, spreadsheet_id, &range, options).await
+}
+
+/// Deserializes `range` (A1 notation) of a spreadsheet read from `source`.
+#[instrument(skip(source, options))]
+pub async fn from_source_range<T, S>(
+    source: &S,
+    spreadsheet_id: &str,
+    range: &str,
+    options: &Options,
+) -> Result<T>
+where
+    T: DeserializeOwned,
+    S: SheetSource,
+{
+    let spreadsheet = source
+        .grid_data(spreadsheet_id, range, &field_mask(options))
+        .await?;
+
+    from_fetched_spreadsheet(spreadsheet, options)
+}
+
+#[cfg(test)]
+pub(crate) fn fixture_spreadsheet(sheets: Vec<(&str, Vec<Vec<&str>>)>) -> Spreadsheet {
+    use crate::de::{grid_data, string_cell};
+    use google_sheets4::api::SheetProperties;
+
+    Spreadsheet {
+        sheets: Some(
+            sheets
+                .into_iter()
+                .enumerate()
+                .map(|(idx, (title, rows))| Sheet {
+                    properties: Some(SheetProperties {
+                        sheet_id: Some(idx as i32),
+                        title: Some(title.to_owned()),
+                        ..SheetProperties::default()
+                    }),
+                    data: Some(vec![grid_data(
+                        rows.into_iter()
+                            .map(|row| row.into_iter().map(string_cell).collect())
+                            .collect(),
+                    )]),
+                    ..Sheet::default()
+                })
+                .collect(),
+        ),
+        ..Spreadsheet::default()
+    }
+}
+
+#[tokio::test]
+async fn test_fixture_source() {
+    let mut fixture = Fixture::new();
+    fixture.insert(
+        "abc",
+        fixture_spreadsheet(vec![
+            ("First", vec![vec!["name"], vec!["Apple"], vec!["Pear"]]),
+            (
+                "Second",
+                vec![
+                    vec!["a", "b", "c"],
+                    vec!["1", "2", "3"],
+                    vec!["4", "5", "6"],
+                ],
+            ),
+        ]),
+    );
+
+    let metadata = fixture.spreadsheet("abc").await.unwrap();
+    assert!(metadata.sheets.unwrap().iter().all(|s| s.data.is_none()));
+
+    let result: Vec<HashMap<String, String>> = from_source(&fixture, "abc", &Options::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        vec!["Apple", "Pear"],
+        result.iter().map(|r| &r["name"]).collect::<Vec<_>>()
+    );
+
+    let result: Vec<HashMap<String, String>> =
+        from_source_range(&fixture, "abc", "Second!B1:C2", &Options::default())
+            .await
+            .unwrap();
+    assert_eq!(
+        vec![HashMap::from([
+            ("b".to_owned(), "2".to_owned()),
+            ("c".to_owned(), "3".to_owned())
+        ])],
+        result
+    );
+
+    let result: Result<Vec<HashMap<String, String>>> =
+        from_source(&fixture, "missing", &Options::default()).await;
+    assert!(matches!(result, Err(Error::UnknownSpreadsheet(_))));
+}
+
+#[tokio::test]
+async fn test_json_dir_source() {
+    let dir = std::env::temp_dir().join(format!("serde-google-sheets-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let spreadsheet = fixture_spreadsheet(vec![("Sheet1", vec![vec!["name"], vec!["Apple"]])]);
+    std::fs::write(
+        dir.join("abc.json"),
+        serde_json::to_vec(&spreadsheet).unwrap(),
+    )
+    .unwrap();
+
+    let source = JsonDir::new(&dir);
+    let result: Result<Vec<HashMap<String, String>>> =
+        from_source(&source, "abc", &Options::default()).await;
+
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!("Apple", result.unwrap()[0]["name"]);
+}
+
+#[tokio::test]
+async fn test_drive_version() {
+    use crate::de::{serve_json, test_hub};
+
+    let (addr, requests) = serve_json(r#"{"version": "42"}"#.to_owned()).await;
+    let files_url = format!("http://{}/drive/v3/files/", addr);
+
+    let version = drive_version(&test_hub(addr), &files_url, "abc")
+        .await
+        .unwrap();
+
+    assert_eq!(Some("42".to_owned()), version);
+    assert_eq!(
+        "/drive/v3/files/abc?fields=version&supportsAllDrives=true",
+        requests.lock().unwrap()[0]
+    );
+}
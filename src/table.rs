@@ -0,0 +1,242 @@
+use std::ops::Range;
+
+use google_sheets4::api::{GridData, GridRange};
+use serde::de::DeserializeOwned;
+use tracing::instrument;
+
+use crate::de::from_grid_data_with_options;
+use crate::error::{Error, Result};
+use crate::options::Options;
+use crate::range::slice_grid_data;
+
+/// A rectangular block of cells separated from the rest of the sheet by
+/// blank rows and columns, such as one of several tables on a dashboard tab.
+#[derive(Debug, Clone)]
+pub struct Table {
+    /// Bounds of the table in sheet coordinates (end exclusive).
+    pub range: GridRange,
+
+    /// Text of the table's first row.
+    pub headers: Vec<String>,
+}
+
+impl Table {
+    /// Whether every header in `headers` is one of the table's headers.
+    pub fn has_headers(&self, headers: &[&str]) -> bool {
+        headers
+            .iter()
+            .all(|header| self.headers.iter().any(|h| h == header))
+    }
+}
+
+/// Finds the tables in `grid_data`, top to bottom and then left to right.
+///
+/// The grid is cut along rows and columns that are blank across the whole
+/// region being split, repeatedly, until no region can be cut further. Each
+/// remaining region is a table whose first row is its header.
+pub fn find_tables(grid_data: &GridData) -> Vec<Table> {
+    let rows: Vec<&[_]> = grid_data
+        .row_data
+        .iter()
+        .flatten()
+        .map(|row| row.values.as_deref().unwrap_or_default())
+        .collect();
+    let filled = |row: usize, column: usize| {
+        rows[row]
+            .get(column)
+            .is_some_and(|cell| cell.effective_value.is_some())
+    };
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+
+    let mut regions = Vec::new();
+    split(&filled, 0..rows.len(), 0..width, &mut regions);
+
+    let start_row = grid_data.start_row.unwrap_or(0);
+    let start_column = grid_data.start_column.unwrap_or(0);
+
+    regions
+        .into_iter()
+        .map(|(rows_range, columns)| Table {
+            headers: columns
+                .clone()
+                .map(|column| {
+                    rows[rows_range.start]
+                        .get(column)
+                        .and_then(|cell| cell.formatted_value.clone())
+                        .unwrap_or_default()
+                })
+                .collect(),
+            range: GridRange {
+                start_row_index: Some(start_row + rows_range.start as i32),
+                end_row_index: Some(start_row + rows_range.end as i32),
+                start_column_index: Some(start_column + columns.start as i32),
+                end_column_index: Some(start_column + columns.end as i32),
+                ..GridRange::default()
+            },
+        })
+        .collect()
+}
+
+type Region = (Range<usize>, Range<usize>);
+
+fn split(
+    filled: &impl Fn(usize, usize) -> bool,
+    rows: Range<usize>,
+    columns: Range<usize>,
+    regions: &mut Vec<Region>,
+) {
+    let row_bands = bands(rows, |row| {
+        columns.clone().any(|column| filled(row, column))
+    });
+    if row_bands.len() != 1 {
+        for band in row_bands {
+            split(filled, band, columns.clone(), regions);
+        }
+        return;
+    }
+
+    let rows = row_bands[0].clone();
+    let column_bands = bands(columns, |column| {
+        rows.clone().any(|row| filled(row, column))
+    });
+    if column_bands.len() != 1 {
+        for band in column_bands {
+            split(filled, rows.clone(), band, regions);
+        }
+        return;
+    }
+
+    regions.push((rows, column_bands[0].clone()));
+}
+
+// Runs of consecutive indices for which `filled` holds.
+fn bands(range: Range<usize>, filled: impl Fn(usize) -> bool) -> Vec<Range<usize>> {
+    let mut bands = Vec::new();
+    let mut start = None;
+
+    for idx in range.clone() {
+        match (filled(idx), start) {
+            (true, None) => start = Some(idx),
+            (false, Some(s)) => {
+                bands.push(s..idx);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(s) = start {
+        bands.push(s..range.end);
+    }
+
+    bands
+}
+
+/// Deserializes one table of `grid_data`, as found by [`find_tables`].
+pub fn from_table<T>(grid_data: &GridData, table: &Table, options: &Options) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    from_grid_data_with_options(&slice_grid_data(grid_data, &table.range), options)
+}
+
+/// Deserializes the `index`th table of `grid_data` (0-based, in the order of
+/// [`find_tables`]).
+#[instrument(skip(grid_data, options))]
+pub fn from_table_index<T>(grid_data: &GridData, index: usize, options: &Options) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let table = find_tables(grid_data)
+        .into_iter()
+        .nth(index)
+        .ok_or_else(|| Error::MissingTable(index.to_string()))?;
+
+    from_table(grid_data, &table, options)
+}
+
+/// Deserializes the first table of `grid_data` whose header row contains all
+/// of `headers`.
+#[instrument(skip(grid_data, options))]
+pub fn from_table_with_headers<T>(
+    grid_data: &GridData,
+    headers: &[&str],
+    options: &Options,
+) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let table = find_tables(grid_data)
+        .into_iter()
+        .find(|table| table.has_headers(headers))
+        .ok_or_else(|| Error::MissingTable(format!("with headers {:?}", headers)))?;
+
+    from_table(grid_data, &table, options)
+}
+
+#[test]
+fn test_tables() {
+    use crate::de::{grid_data, string_cell};
+    use google_sheets4::api::CellData;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Sale {
+        region: String,
+        total: String,
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Note {
+        note: String,
+    }
+
+    let cell = |s: &str| match s {
+        "" => CellData::default(),
+        s => string_cell(s),
+    };
+    let mut data = grid_data(
+        [
+            vec!["region", "total", "", "note"],
+            vec!["north", "10", "", "check totals"],
+            vec!["south", "20", "", ""],
+            vec![],
+            vec!["owner", "", "", ""],
+            vec!["ann", "", "", ""],
+        ]
+        .into_iter()
+        .map(|row| row.into_iter().map(cell).collect())
+        .collect(),
+    );
+    data.start_row = Some(2);
+
+    let tables = find_tables(&data);
+    let headers: Vec<_> = tables.iter().map(|t| t.headers.clone()).collect();
+    assert_eq!(
+        headers,
+        vec![
+            vec!["region".to_owned(), "total".to_owned()],
+            vec!["note".to_owned()],
+            vec!["owner".to_owned()],
+        ]
+    );
+    assert_eq!(tables[1].range.start_row_index, Some(2));
+    assert_eq!(tables[1].range.end_row_index, Some(4));
+    assert_eq!(tables[2].range.start_row_index, Some(6));
+
+    let sales: Vec<Sale> =
+        from_table_with_headers(&data, &["total", "region"], &Options::default()).unwrap();
+    assert_eq!(sales.len(), 2);
+    assert_eq!(sales[1].total, "20");
+
+    let notes: Vec<Note> = from_table_index(&data, 1, &Options::default()).unwrap();
+    assert_eq!(
+        notes,
+        vec![Note {
+            note: "check totals".to_owned()
+        }]
+    );
+
+    let err = from_table_index::<Vec<Note>>(&data, 3, &Options::default()).unwrap_err();
+    assert!(matches!(err, Error::MissingTable(_)));
+}